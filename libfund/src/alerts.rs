@@ -0,0 +1,299 @@
+//! Alerts about funds that need attention: funds below a threshold set
+//! for them, overdrawn funds, and funds that just reached their goal.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+
+use currency::Currency;
+use history::History;
+use money;
+use {Fund, FundManager};
+
+/// The error returned when parsing an invalid threshold
+#[derive(Debug)]
+pub struct InvalidThresholdError {
+    threshold: String,
+    currency: Currency,
+}
+
+impl fmt::Display for InvalidThresholdError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "'{}' is not a threshold, expected an amount of {} or a percentage of the goal such as 25%",
+            self.threshold, self.currency
+        )
+    }
+}
+
+impl Error for InvalidThresholdError {}
+
+/// How low a fund may get before it is reported
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Threshold {
+    /// An amount in the smallest unit of the fund's currency
+    Amount(i32),
+    /// A percentage of the fund's goal
+    Percent(f64),
+}
+
+impl Threshold {
+    /// Parses `20.00` as an amount of `currency`, the currency of the fund
+    /// the threshold is for, and `25%` as a percentage of the goal
+    ///
+    /// # Errors
+    ///
+    /// * When `threshold` is neither a positive amount of `currency` nor a
+    ///   positive percentage
+    ///
+    /// # Example
+    /// ```
+    /// use libfund::alerts::Threshold;
+    /// use libfund::currency::Currency;
+    ///
+    /// let yen = "JPY".parse().unwrap();
+    /// assert_eq!(Threshold::parse("20.00", Currency::USD).unwrap(), Threshold::Amount(2000));
+    /// assert_eq!(Threshold::parse("2000", yen).unwrap(), Threshold::Amount(2000));
+    /// assert_eq!(Threshold::parse("25%", yen).unwrap(), Threshold::Percent(25.0));
+    /// ```
+    pub fn parse(threshold: &str, currency: Currency) -> Result<Threshold, InvalidThresholdError> {
+        let invalid = || InvalidThresholdError {
+            threshold: String::from(threshold),
+            currency,
+        };
+        let s = threshold.trim();
+        if let Some(percent) = s.strip_suffix('%') {
+            return match percent.trim().parse::<f64>() {
+                Ok(percent) if percent.is_finite() && percent >= 0.0 => {
+                    Ok(Threshold::Percent(percent))
+                }
+                _ => Err(invalid()),
+            };
+        }
+        match money::parse_amount(s, currency) {
+            Ok(amount) if amount >= 0 => Ok(Threshold::Amount(amount)),
+            _ => Err(invalid()),
+        }
+    }
+
+    /// Returns the amount `fund` may not fall below
+    ///
+    /// # Example
+    /// ```
+    /// use libfund::alerts::Threshold;
+    /// use libfund::Fund;
+    ///
+    /// let fund = Fund::new().with_amount(3000).with_goal(20000).build();
+    /// assert_eq!(Threshold::Percent(25.0).limit(&fund), 5000);
+    /// assert_eq!(Threshold::Amount(2000).limit(&fund), 2000);
+    /// ```
+    pub fn limit(self, fund: &Fund) -> i32 {
+        match self {
+            Threshold::Amount(amount) => amount,
+            Threshold::Percent(percent) => (f64::from(fund.goal) * percent / 100.0).round() as i32,
+        }
+    }
+}
+
+/// Why a fund needs attention
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Alert {
+    /// The fund holds less than nothing
+    Overdrawn,
+    /// The fund holds less than `limit`, its threshold
+    Low { limit: i32 },
+    /// The fund's last transaction brought it up to its goal
+    GoalReached,
+}
+
+/// Returns the alerts for every fund, in order of their names. A fund
+/// below zero is reported as overdrawn rather than low.
+///
+/// # Arguments
+///
+/// * `thresholds` - the threshold of each fund that has one, by name
+///
+/// # Example
+/// ```
+/// use std::collections::BTreeMap;
+///
+/// use libfund::alerts::{self, Alert, Threshold};
+/// use libfund::history::History;
+/// use libfund::{Fund, FundManager};
+///
+/// let mut funds = FundManager::new();
+/// funds.add_fund("car", Fund::new().with_amount(-500).build()).unwrap();
+/// funds.add_fund("grocery", Fund::new().with_amount(1500).with_goal(20000).build()).unwrap();
+/// let mut thresholds = BTreeMap::new();
+/// thresholds.insert(String::from("grocery"), Threshold::Percent(10.0));
+///
+/// let found = alerts::alerts(&funds, &History::new(), &thresholds);
+/// assert_eq!(found[0], (String::from("car"), Alert::Overdrawn));
+/// assert_eq!(found[1], (String::from("grocery"), Alert::Low { limit: 2000 }));
+/// ```
+pub fn alerts(
+    funds: &FundManager,
+    history: &History,
+    thresholds: &BTreeMap<String, Threshold>,
+) -> Vec<(String, Alert)> {
+    let mut alerts = Vec::new();
+    for (name, fund) in funds {
+        if fund.amount < 0 {
+            alerts.push((name.clone(), Alert::Overdrawn));
+            continue;
+        }
+        if let Some(threshold) = thresholds.get(name) {
+            let limit = threshold.limit(fund);
+            if fund.amount < limit {
+                alerts.push((name.clone(), Alert::Low { limit }));
+            }
+        }
+        if just_reached_goal(name, fund, history) {
+            alerts.push((name.clone(), Alert::GoalReached));
+        }
+    }
+    alerts
+}
+
+/// Returns the alert raised by a change to a fund from `before` to
+/// `after`, if it went below zero or below `threshold`
+///
+/// # Example
+/// ```
+/// use libfund::alerts::{self, Alert, Threshold};
+/// use libfund::Fund;
+///
+/// let before = Fund::new().with_amount(2500).build();
+/// let after = Fund::new().with_amount(1500).build();
+/// let threshold = Threshold::Amount(2000);
+/// assert_eq!(alerts::crossed(&before, &after, Some(threshold)), Some(Alert::Low { limit: 2000 }));
+/// assert_eq!(alerts::crossed(&after, &after, Some(threshold)), None);
+/// ```
+pub fn crossed(before: &Fund, after: &Fund, threshold: Option<Threshold>) -> Option<Alert> {
+    if after.amount < 0 && before.amount >= 0 {
+        return Some(Alert::Overdrawn);
+    }
+    let limit = threshold?.limit(after);
+    if after.amount < limit && before.amount >= limit {
+        return Some(Alert::Low { limit });
+    }
+    None
+}
+
+/// Whether the last transaction of the fund called `name` took it from
+/// below its goal to at least its goal
+fn just_reached_goal(name: &str, fund: &Fund, history: &History) -> bool {
+    if fund.goal <= 0 || fund.amount < fund.goal {
+        return false;
+    }
+    let last = history
+        .iter()
+        .rev()
+        .find(|transaction| transaction.legs.iter().any(|leg| leg.fund == name));
+    match last {
+        Some(transaction) => {
+            let change: i64 = transaction
+                .legs
+                .iter()
+                .filter(|leg| leg.fund == name)
+                .map(|leg| i64::from(leg.amount))
+                .sum();
+            i64::from(fund.amount) - change < i64::from(fund.goal)
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{alerts, crossed, Alert, Threshold};
+    use chrono::NaiveDate;
+    use currency::Currency;
+    use history::{History, Transaction};
+    use std::collections::BTreeMap;
+    use {Fund, FundManager};
+
+    #[test]
+    fn parses_thresholds() {
+        let usd = Currency::USD;
+        let yen: Currency = "JPY".parse().unwrap();
+        let parse = |threshold, currency| Threshold::parse(threshold, currency).unwrap();
+        assert_eq!(parse("20.00", usd), Threshold::Amount(2000));
+        assert_eq!(parse("20", usd), Threshold::Amount(2000));
+        assert_eq!(parse("7.5", usd), Threshold::Amount(750));
+        assert_eq!(parse("20", yen), Threshold::Amount(20));
+        assert_eq!(parse(" 25% ", yen), Threshold::Percent(25.0));
+        assert!(Threshold::parse("7.5", yen).is_err());
+        for invalid in &[
+            "",
+            "abc",
+            "-5.00",
+            "1.234",
+            ".50",
+            "x%",
+            "-10%",
+            "99999999999",
+        ] {
+            assert!(
+                Threshold::parse(invalid, usd).is_err(),
+                "{} parsed",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn reports_goals_reached_by_the_last_transaction() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        let mut funds = FundManager::new();
+        funds
+            .add_fund(
+                "trip",
+                Fund::new().with_amount(20000).with_goal(20000).build(),
+            )
+            .unwrap();
+        funds
+            .add_fund(
+                "car",
+                Fund::new().with_amount(60000).with_goal(50000).build(),
+            )
+            .unwrap();
+        funds
+            .add_fund(
+                "rent",
+                Fund::new().with_amount(90000).with_goal(90000).build(),
+            )
+            .unwrap();
+        let mut history = History::new();
+        history.push(Transaction::new(today).with_leg("trip", 5000).build());
+        history.push(Transaction::new(today).with_leg("car", 5000).build());
+        history.push(Transaction::new(today).with_leg("rent", 90000).build());
+        history.push(
+            Transaction::new(today)
+                .with_leg("rent", -100)
+                .with_leg("car", 100)
+                .build(),
+        );
+
+        let found = alerts(&funds, &history, &BTreeMap::new());
+        assert_eq!(found, vec![(String::from("trip"), Alert::GoalReached)]);
+    }
+
+    #[test]
+    fn reports_crossing_only_once() {
+        let fund = |amount| Fund::new().with_amount(amount).with_goal(10000).build();
+        let threshold = Some(Threshold::Percent(20.0));
+        assert_eq!(
+            crossed(&fund(2500), &fund(1999), threshold),
+            Some(Alert::Low { limit: 2000 })
+        );
+        assert_eq!(crossed(&fund(1999), &fund(1000), threshold), None);
+        assert_eq!(
+            crossed(&fund(1000), &fund(-1), threshold),
+            Some(Alert::Overdrawn)
+        );
+        assert_eq!(crossed(&fund(-1), &fund(-500), threshold), None);
+        assert_eq!(crossed(&fund(2500), &fund(1000), None), None);
+    }
+}
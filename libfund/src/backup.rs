@@ -0,0 +1,399 @@
+//! A JSON document holding every fund, transaction and exchange rate, for
+//! backups and for moving data between machines and versions.
+//!
+//! The document has its own record types rather than serializing the
+//! library's types directly, so its layout only changes when `VERSION`
+//! does.
+
+use std::collections::BTreeMap;
+use std::io;
+
+use chrono::NaiveDate;
+use serde_json;
+
+use currency::{Currency, Rates};
+use history::{History, Transaction};
+use {Fund, FundManager, MergeError, MergeReport, MergeStrategy};
+
+/// The version of the document layout written by `Backup::to_json`
+pub const VERSION: u32 = 1;
+
+/// What happened to the incoming data during `Backup::merge`
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct MergeSummary {
+    pub funds: MergeReport,
+    /// How many transactions were added to the history
+    pub transactions: usize,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+struct FundRecord {
+    amount: i32,
+    goal: i32,
+    currency: String,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+struct LegRecord {
+    fund: String,
+    amount: i32,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+struct TransactionRecord {
+    date: String,
+    legs: Vec<LegRecord>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rate: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    import_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    payee: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    memo: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+struct RateRecord {
+    from: String,
+    to: String,
+    rate: f64,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+struct Document {
+    version: u32,
+    funds: BTreeMap<String, FundRecord>,
+    history: Vec<TransactionRecord>,
+    rates: Vec<RateRecord>,
+}
+
+/// Everything FundWarrior stores, gathered in one place
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Backup {
+    pub funds: FundManager,
+    pub history: History,
+    pub rates: Rates,
+}
+
+impl Backup {
+    pub fn new(funds: FundManager, history: History, rates: Rates) -> Backup {
+        Backup {
+            funds,
+            history,
+            rates,
+        }
+    }
+
+    /// Returns the backup as a pretty-printed JSON document
+    ///
+    /// # Example
+    /// ```
+    /// use libfund::backup::Backup;
+    /// use libfund::currency::Rates;
+    /// use libfund::history::History;
+    /// use libfund::{Fund, FundManager};
+    ///
+    /// let mut funds = FundManager::new();
+    /// funds.add_fund("grocery", Fund::new().with_amount(100).build()).unwrap();
+    /// let backup = Backup::new(funds, History::new(), Rates::new());
+    /// assert_eq!(Backup::from_json(&backup.to_json()).unwrap(), backup);
+    /// ```
+    pub fn to_json(&self) -> String {
+        let document = Document {
+            version: VERSION,
+            funds: (&self.funds)
+                .into_iter()
+                .map(|(name, fund)| {
+                    let record = FundRecord {
+                        amount: fund.amount,
+                        goal: fund.goal,
+                        currency: fund.currency.to_string(),
+                    };
+                    (name.clone(), record)
+                })
+                .collect(),
+            history: self
+                .history
+                .iter()
+                .map(|transaction| TransactionRecord {
+                    date: transaction.date.format("%Y-%m-%d").to_string(),
+                    legs: transaction
+                        .legs
+                        .iter()
+                        .map(|leg| LegRecord {
+                            fund: leg.fund.clone(),
+                            amount: leg.amount,
+                        })
+                        .collect(),
+                    rate: transaction.rate,
+                    import_id: transaction.import_id.clone(),
+                    payee: transaction.payee.clone(),
+                    memo: transaction.memo.clone(),
+                })
+                .collect(),
+            rates: self
+                .rates
+                .iter()
+                .map(|(from, to, rate)| RateRecord {
+                    from: from.to_string(),
+                    to: to.to_string(),
+                    rate,
+                })
+                .collect(),
+        };
+        let mut json = serde_json::to_string_pretty(&document).unwrap_or_default();
+        json.push('\n');
+        json
+    }
+
+    /// Reads a backup from a JSON document written by `to_json`
+    ///
+    /// # Errors
+    ///
+    /// * When the document is not valid JSON or is missing fields
+    /// * When the document was written by a newer version
+    /// * When a date or currency is invalid
+    pub fn from_json(json: &str) -> Result<Backup, io::Error> {
+        let invalid = |message: String| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("while parsing backup: {}", message),
+            )
+        };
+        let document: Document = serde_json::from_str(json).map_err(|e| invalid(e.to_string()))?;
+        if document.version > VERSION {
+            return Err(invalid(format!(
+                "it was written by a newer version (format {}, this version reads up to {})",
+                document.version, VERSION
+            )));
+        }
+        let currency = |code: &str| code.parse::<Currency>().map_err(|e| invalid(e.to_string()));
+
+        let mut funds = FundManager::new();
+        for (name, record) in document.funds {
+            let fund = Fund::new()
+                .with_amount(record.amount)
+                .with_goal(record.goal)
+                .with_currency(currency(&record.currency)?)
+                .build();
+            funds
+                .add_fund(&name, fund)
+                .map_err(|e| invalid(e.to_string()))?;
+        }
+
+        let mut history = History::new();
+        for record in document.history {
+            let date = NaiveDate::parse_from_str(&record.date, "%Y-%m-%d")
+                .map_err(|_| invalid(format!("invalid date {:?}", record.date)))?;
+            let mut transaction = Transaction::new(date);
+            for leg in &record.legs {
+                transaction.with_leg(&leg.fund, leg.amount);
+            }
+            if let Some(rate) = record.rate {
+                transaction.with_rate(rate);
+            }
+            if let Some(ref import_id) = record.import_id {
+                transaction.with_import_id(import_id);
+            }
+            if let Some(ref payee) = record.payee {
+                transaction.with_payee(payee);
+            }
+            if let Some(ref memo) = record.memo {
+                transaction.with_memo(memo);
+            }
+            history.push(transaction.build());
+        }
+
+        let mut rates = Rates::new();
+        for record in document.rates {
+            rates.set(currency(&record.from)?, currency(&record.to)?, record.rate);
+        }
+        Ok(Backup::new(funds, history, rates))
+    }
+
+    /// Adds the funds, transactions and exchange rates of `incoming`,
+    /// settling funds with taken names as `strategy` says. Transactions
+    /// of renamed funds follow them, while transactions already in the
+    /// history are not added again, and neither are those involving funds
+    /// that were kept. Existing exchange rates are only replaced when
+    /// taking incoming funds.
+    ///
+    /// # Errors
+    ///
+    /// * When the funds could not be merged, in which case nothing is
+    ///   changed
+    pub fn merge(
+        &mut self,
+        incoming: Backup,
+        strategy: MergeStrategy,
+    ) -> Result<MergeSummary, MergeError> {
+        let report = self.funds.merge(incoming.funds, strategy)?;
+        let mut incoming_history = incoming.history;
+        for (old_name, new_name) in &report.renamed {
+            incoming_history.rename_fund(old_name, new_name);
+        }
+
+        let mut existing: Vec<&Transaction> = self.history.iter().collect();
+        let mut added = Vec::new();
+        for transaction in incoming_history.iter() {
+            if transaction
+                .legs
+                .iter()
+                .any(|leg| report.kept.contains(&leg.fund))
+            {
+                continue;
+            }
+            match existing
+                .iter()
+                .position(|&existing| existing == transaction)
+            {
+                Some(index) => {
+                    existing.remove(index);
+                }
+                None => added.push(transaction.clone()),
+            }
+        }
+        let transactions = added.len();
+        for transaction in added {
+            self.history.push(transaction);
+        }
+
+        for (from, to, rate) in incoming.rates.iter() {
+            if strategy == MergeStrategy::TakeIncoming || self.rates.rate(from, to).is_none() {
+                self.rates.set(from, to, rate);
+            }
+        }
+        Ok(MergeSummary {
+            funds: report,
+            transactions,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Backup;
+    use chrono::NaiveDate;
+    use currency::{Currency, Rates};
+    use history::{History, Transaction};
+    use {Fund, FundManager, MergeStrategy};
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    fn backup(amount: i32) -> Backup {
+        let eur: Currency = "EUR".parse().unwrap();
+        let mut funds = FundManager::new();
+        funds
+            .add_fund(
+                "grocery",
+                Fund::new().with_amount(amount).with_goal(500).build(),
+            )
+            .unwrap();
+        funds
+            .add_fund(
+                "trip",
+                Fund::new().with_amount(20).with_currency(eur).build(),
+            )
+            .unwrap();
+        let mut history = History::new();
+        history.push(
+            Transaction::new(date(1))
+                .with_leg("grocery", amount)
+                .build(),
+        );
+        history.push(
+            Transaction::new(date(2))
+                .with_leg("grocery", -10)
+                .with_leg("trip", 9)
+                .with_rate(0.925_925_925_925_9)
+                .with_import_id("csv:1")
+                .with_payee("Travel agent")
+                .with_memo("flights")
+                .build(),
+        );
+        let mut rates = Rates::new();
+        rates.set(eur, Currency::USD, 1.08);
+        Backup::new(funds, history, rates)
+    }
+
+    #[test]
+    fn round_trips_json() {
+        let backup = backup(100);
+        let json = backup.to_json();
+        assert!(json.contains("\"version\": 1"));
+        assert_eq!(Backup::from_json(&json).unwrap(), backup);
+        assert!(Backup::from_json(
+            "{\"version\": 99, \"funds\": {}, \"history\": [], \"rates\": []}"
+        )
+        .is_err());
+        assert!(Backup::from_json("[]").is_err());
+    }
+
+    #[test]
+    fn skips_conflicting_funds() {
+        let mut existing = backup(100);
+        let report = existing
+            .merge(backup(300), MergeStrategy::KeepExisting)
+            .unwrap();
+        assert_eq!(report.funds.kept, vec!["grocery", "trip"]);
+        assert_eq!(report.transactions, 0);
+        assert_eq!(existing, backup(100));
+    }
+
+    #[test]
+    fn overwrites_conflicting_funds() {
+        let mut existing = backup(100);
+        let report = existing
+            .merge(backup(300), MergeStrategy::TakeIncoming)
+            .unwrap();
+        assert_eq!(report.funds.replaced, vec!["grocery", "trip"]);
+        assert_eq!(existing.funds.fund("grocery").unwrap().amount, 300);
+        // The deposit differs, the transfer is already there
+        assert_eq!(report.transactions, 1);
+        assert_eq!(existing.history.len(), 3);
+    }
+
+    #[test]
+    fn sums_or_refuses_conflicting_funds() {
+        let mut existing = backup(100);
+        let report = existing
+            .merge(backup(300), MergeStrategy::SumBalances)
+            .unwrap();
+        assert_eq!(report.funds.summed, vec!["grocery", "trip"]);
+        assert_eq!(existing.funds.fund("grocery").unwrap().amount, 400);
+
+        let mut existing = backup(100);
+        assert!(existing.merge(backup(300), MergeStrategy::Error).is_err());
+        assert_eq!(existing, backup(100));
+    }
+
+    #[test]
+    fn renames_conflicting_funds() {
+        let mut existing = backup(100);
+        let mut incoming = backup(300);
+        incoming
+            .funds
+            .add_fund("grocery-2", Fund::new().build())
+            .unwrap();
+        let report = existing
+            .merge(incoming, MergeStrategy::RenameIncoming)
+            .unwrap();
+        assert_eq!(report.funds.added, vec!["grocery-2"]);
+        assert_eq!(
+            report.funds.renamed,
+            vec![
+                (String::from("grocery"), String::from("grocery-3")),
+                (String::from("trip"), String::from("trip-2")),
+            ]
+        );
+        assert_eq!(existing.funds.fund("grocery-3").unwrap().amount, 300);
+        assert_eq!(report.transactions, 2);
+        assert!(existing
+            .history
+            .iter()
+            .any(|t| t.legs[0].fund == "grocery-3"));
+    }
+}
@@ -0,0 +1,355 @@
+//! ISO 4217 currencies and a user maintained table of exchange rates
+//! between them.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::io::prelude::*;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+use std::str::FromStr;
+
+use money::MoneyFormat;
+
+/// Code, number of minor units and symbol of every supported currency
+const CURRENCIES: &[(&str, u32, &str)] = &[
+    ("AED", 2, "AED"),
+    ("ARS", 2, "AR$"),
+    ("AUD", 2, "A$"),
+    ("BHD", 3, "BHD"),
+    ("BRL", 2, "R$"),
+    ("CAD", 2, "CA$"),
+    ("CHF", 2, "CHF"),
+    ("CLP", 0, "CLP$"),
+    ("CNY", 2, "CN¥"),
+    ("COP", 2, "COL$"),
+    ("CZK", 2, "Kč"),
+    ("DKK", 2, "kr"),
+    ("EGP", 2, "E£"),
+    ("EUR", 2, "€"),
+    ("GBP", 2, "£"),
+    ("HKD", 2, "HK$"),
+    ("HUF", 2, "Ft"),
+    ("IDR", 2, "Rp"),
+    ("ILS", 2, "₪"),
+    ("INR", 2, "₹"),
+    ("IQD", 3, "IQD"),
+    ("ISK", 0, "kr"),
+    ("JOD", 3, "JOD"),
+    ("JPY", 0, "¥"),
+    ("KRW", 0, "₩"),
+    ("KWD", 3, "KWD"),
+    ("LYD", 3, "LYD"),
+    ("MXN", 2, "MX$"),
+    ("MYR", 2, "RM"),
+    ("NGN", 2, "₦"),
+    ("NOK", 2, "kr"),
+    ("NZD", 2, "NZ$"),
+    ("OMR", 3, "OMR"),
+    ("PHP", 2, "₱"),
+    ("PKR", 2, "Rs"),
+    ("PLN", 2, "zł"),
+    ("PYG", 0, "₲"),
+    ("RON", 2, "lei"),
+    ("RUB", 2, "₽"),
+    ("SAR", 2, "SAR"),
+    ("SEK", 2, "kr"),
+    ("SGD", 2, "S$"),
+    ("THB", 2, "฿"),
+    ("TND", 3, "TND"),
+    ("TRY", 2, "₺"),
+    ("TWD", 2, "NT$"),
+    ("UAH", 2, "₴"),
+    ("UGX", 0, "USh"),
+    ("USD", 2, "$"),
+    ("VND", 0, "₫"),
+    ("ZAR", 2, "R"),
+];
+
+/// The error returned when parsing a currency code that isn't in ISO 4217
+#[derive(Debug)]
+pub struct UnknownCurrencyError {
+    code: String,
+}
+
+impl fmt::Display for UnknownCurrencyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "'{}' is not a known ISO 4217 currency code", self.code)
+    }
+}
+
+impl Error for UnknownCurrencyError {}
+
+/// The error returned when converting between two currencies that have
+/// no exchange rate set
+#[derive(Debug)]
+pub struct NoRateError {
+    pub from: Currency,
+    pub to: Currency,
+}
+
+impl fmt::Display for NoRateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "no exchange rate from {} to {}. Set one with 'fund rate set {} {} <rate>'",
+            self.from, self.to, self.from, self.to
+        )
+    }
+}
+
+impl Error for NoRateError {}
+
+/// A currency, such as `USD` or `JPY`
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
+pub struct Currency {
+    code: &'static str,
+    minor_units: u32,
+    symbol: &'static str,
+}
+
+impl Default for Currency {
+    fn default() -> Currency {
+        Currency::USD
+    }
+}
+
+impl Currency {
+    /// The United States dollar, which funds use unless told otherwise
+    pub const USD: Currency = Currency {
+        code: "USD",
+        minor_units: 2,
+        symbol: "$",
+    };
+
+    /// Returns the three letter ISO 4217 code
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+
+    /// Returns how many digits of an amount are the minor unit, e.g. 2
+    /// for `USD` (cents) and 0 for `JPY`
+    pub fn minor_units(&self) -> u32 {
+        self.minor_units
+    }
+
+    /// Returns the symbol used when writing amounts, e.g. `€`
+    pub fn symbol(&self) -> &'static str {
+        self.symbol
+    }
+
+    /// Returns `format` with this currency's symbol and number of decimals
+    ///
+    /// # Example
+    /// ```
+    /// use libfund::currency::Currency;
+    /// use libfund::money::MoneyFormat;
+    ///
+    /// let yen: Currency = "JPY".parse().unwrap();
+    /// assert_eq!(yen.money_format(&MoneyFormat::new()).format(1500), "¥1,500");
+    /// ```
+    pub fn money_format(&self, format: &MoneyFormat) -> MoneyFormat {
+        format
+            .clone()
+            .with_symbol(self.symbol)
+            .with_decimals(self.minor_units)
+            .build()
+    }
+}
+
+impl FromStr for Currency {
+    type Err = UnknownCurrencyError;
+
+    fn from_str(s: &str) -> Result<Currency, UnknownCurrencyError> {
+        let code = s.to_uppercase();
+        match CURRENCIES.iter().find(|currency| currency.0 == code) {
+            Some(&(code, minor_units, symbol)) => Ok(Currency {
+                code,
+                minor_units,
+                symbol,
+            }),
+            None => Err(UnknownCurrencyError {
+                code: String::from(s),
+            }),
+        }
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.code)
+    }
+}
+
+/// A table of exchange rates, each saying how many units of one currency
+/// a single unit of another is worth
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Rates {
+    rates: BTreeMap<(Currency, Currency), f64>,
+}
+
+impl Rates {
+    pub fn new() -> Rates {
+        Rates {
+            rates: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the rates stored in `ratesfile`, or an empty table if the
+    /// file does not exist yet
+    ///
+    /// # Errors
+    ///
+    /// * When the file could not be read
+    /// * When the file could not be parsed correctly
+    pub fn load(ratesfile: &Path) -> Result<Rates, io::Error> {
+        let mut rates = Rates::new();
+        let file = match File::open(ratesfile) {
+            Ok(file) => file,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(rates),
+            Err(e) => return Err(e),
+        };
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            let invalid = |e: &dyn fmt::Display| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("while parsing {:?}: {}", ratesfile, e),
+                )
+            };
+            let rate_info: Vec<&str> = line.split(':').collect();
+            if rate_info.len() != 3 {
+                return Err(invalid(&"expected 'FROM:TO:RATE'"));
+            }
+            let from: Currency = rate_info[0].parse().map_err(|e| invalid(&e))?;
+            let to: Currency = rate_info[1].parse().map_err(|e| invalid(&e))?;
+            let rate: f64 = rate_info[2].parse().map_err(|e| invalid(&e))?;
+            rates.set(from, to, rate);
+        }
+        Ok(rates)
+    }
+
+    /// Saves the rates to `ratesfile`
+    ///
+    /// # Errors
+    ///
+    /// * When the file or its directory could not be created or written to
+    pub fn save(&self, ratesfile: &Path) -> Result<(), io::Error> {
+        fs::create_dir_all(ratesfile.parent().unwrap_or(ratesfile))?;
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(ratesfile)?;
+        let mut buf_writer = BufWriter::new(file);
+        for (&(from, to), rate) in &self.rates {
+            writeln!(buf_writer, "{}:{}:{}", from, to, rate)?;
+        }
+        Ok(())
+    }
+
+    /// Sets how many units of `to` one unit of `from` is worth
+    pub fn set(&mut self, from: Currency, to: Currency, rate: f64) {
+        self.rates.insert((from, to), rate);
+    }
+
+    /// Returns how many units of `to` one unit of `from` is worth, using
+    /// the inverse of the opposite rate if only that one has been set
+    ///
+    /// # Example
+    /// ```
+    /// use libfund::currency::{Currency, Rates};
+    ///
+    /// let eur: Currency = "EUR".parse().unwrap();
+    /// let mut rates = Rates::new();
+    /// rates.set(eur, Currency::USD, 1.25);
+    /// assert_eq!(rates.rate(eur, Currency::USD), Some(1.25));
+    /// assert_eq!(rates.rate(Currency::USD, eur), Some(0.8));
+    /// ```
+    pub fn rate(&self, from: Currency, to: Currency) -> Option<f64> {
+        if from == to {
+            return Some(1.0);
+        }
+        match self.rates.get(&(from, to)) {
+            Some(rate) => Some(*rate),
+            None => self.rates.get(&(to, from)).map(|rate| 1.0 / rate),
+        }
+    }
+
+    /// Converts `amount`, in minor units of `from`, into minor units of `to`,
+    /// rounding to the nearest unit
+    ///
+    /// # Errors
+    ///
+    /// * When there is no rate between the two currencies
+    ///
+    /// # Example
+    /// ```
+    /// use libfund::currency::{Currency, Rates};
+    ///
+    /// let yen: Currency = "JPY".parse().unwrap();
+    /// let mut rates = Rates::new();
+    /// rates.set(Currency::USD, yen, 150.0);
+    /// assert_eq!(rates.convert(250, Currency::USD, yen).unwrap(), 375);
+    /// ```
+    pub fn convert(&self, amount: i64, from: Currency, to: Currency) -> Result<i64, NoRateError> {
+        let rate = match self.rate(from, to) {
+            Some(rate) => rate,
+            None => return Err(NoRateError { from, to }),
+        };
+        let scale = 10f64.powi(to.minor_units as i32 - from.minor_units as i32);
+        Ok((amount as f64 * rate * scale).round() as i64)
+    }
+
+    /// Returns an iterator over every stored rate
+    pub fn iter(&self) -> impl Iterator<Item = (Currency, Currency, f64)> + '_ {
+        self.rates
+            .iter()
+            .map(|(&(from, to), &rate)| (from, to, rate))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Currency, Rates};
+    use std::env;
+
+    #[test]
+    fn parses_codes() {
+        let euro: Currency = "eur".parse().unwrap();
+        assert_eq!(euro.code(), "EUR");
+        assert_eq!(euro.minor_units(), 2);
+        let dinar: Currency = "KWD".parse().unwrap();
+        assert_eq!(dinar.minor_units(), 3);
+        assert!("XYZ".parse::<Currency>().is_err());
+        assert_eq!("USD".parse::<Currency>().unwrap(), Currency::USD);
+    }
+
+    #[test]
+    fn converts_between_minor_units() {
+        let yen: Currency = "JPY".parse().unwrap();
+        let euro: Currency = "EUR".parse().unwrap();
+        let mut rates = Rates::new();
+        rates.set(euro, Currency::USD, 1.08);
+        rates.set(Currency::USD, yen, 150.0);
+        assert_eq!(rates.convert(10000, euro, Currency::USD).unwrap(), 10800);
+        assert_eq!(rates.convert(10800, Currency::USD, euro).unwrap(), 10000);
+        assert_eq!(rates.convert(15000, yen, Currency::USD).unwrap(), 10000);
+        assert!(rates.convert(100, euro, yen).is_err());
+        assert_eq!(rates.convert(100, yen, yen).unwrap(), 100);
+    }
+
+    #[test]
+    fn load_and_save() {
+        let mut path = env::temp_dir();
+        path.push(format!("libfund-rates-{}", std::process::id()));
+        let mut rates = Rates::new();
+        rates.set("EUR".parse().unwrap(), Currency::USD, 1.08);
+        rates.save(&path).unwrap();
+        assert_eq!(Rates::load(&path).unwrap(), rates);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,623 @@
+//! Writing funds and their history in the journal formats of plain-text
+//! accounting tools.
+//!
+//! Every fund becomes an account below a common prefix, such as
+//! `Assets:Checking:Funds:grocery`. The part of each fund's balance that
+//! the history does not account for is booked as an opening balance
+//! against equity, and every transaction becomes a balanced entry, with
+//! spending booked to an expense account per fund and deposits to income.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fmt;
+use std::fmt::Write;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+
+use currency::Currency;
+use history::{History, Transaction};
+use FundManager;
+
+/// The account deposits into funds are booked against
+const INCOME_ACCOUNT: &str = "Income:Deposits";
+
+/// The error returned when parsing an unknown journal format
+#[derive(Debug)]
+pub struct UnknownFormatError {
+    format: String,
+}
+
+impl fmt::Display for UnknownFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "unknown format '{}', expected ledger, hledger or beancount",
+            self.format
+        )
+    }
+}
+
+impl Error for UnknownFormatError {}
+
+/// A plain-text accounting journal format
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum JournalFormat {
+    Ledger,
+    Hledger,
+    Beancount,
+}
+
+impl FromStr for JournalFormat {
+    type Err = UnknownFormatError;
+
+    fn from_str(s: &str) -> Result<JournalFormat, UnknownFormatError> {
+        match s {
+            "ledger" => Ok(JournalFormat::Ledger),
+            "hledger" => Ok(JournalFormat::Hledger),
+            "beancount" => Ok(JournalFormat::Beancount),
+            _ => Err(UnknownFormatError {
+                format: String::from(s),
+            }),
+        }
+    }
+}
+
+impl JournalFormat {
+    fn equity_account(self) -> &'static str {
+        match self {
+            JournalFormat::Beancount => "Equity:Opening-Balances",
+            _ => "Equity:Opening Balances",
+        }
+    }
+
+    /// Turns `name` into an account name part the format accepts.
+    /// Beancount only allows letters, digits and dashes, starting with a
+    /// capital letter or digit, in each part of an account name.
+    fn account_part(self, name: &str) -> String {
+        match self {
+            JournalFormat::Beancount => {
+                let mut part: String = name
+                    .chars()
+                    .map(|c| if c.is_alphanumeric() { c } else { '-' })
+                    .collect();
+                match part.chars().next() {
+                    Some(first) if first.is_alphanumeric() => {
+                        let rest = part.split_off(first.len_utf8());
+                        first.to_uppercase().chain(rest.chars()).collect()
+                    }
+                    _ => format!("X{}", part),
+                }
+            }
+            _ => name
+                .replace(':', "-")
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" "),
+        }
+    }
+}
+
+/// The account name part of every fund in a journal. Funds whose names
+/// turn into the same part, such as `grocery` and `Grocery` in beancount,
+/// are told apart by a numbered suffix, so their balances stay separate.
+struct Accounts {
+    parts: BTreeMap<String, String>,
+}
+
+impl Accounts {
+    fn new<'a, I: IntoIterator<Item = &'a str>>(format: JournalFormat, names: I) -> Accounts {
+        let names: BTreeSet<&str> = names.into_iter().collect();
+        let mut used = BTreeSet::new();
+        let mut parts = BTreeMap::new();
+        for name in names {
+            let base = format.account_part(name);
+            let mut part = base.clone();
+            let mut suffix = 1;
+            while used.contains(&part) {
+                suffix += 1;
+                part = format!("{}-{}", base, suffix);
+            }
+            used.insert(part.clone());
+            parts.insert(String::from(name), part);
+        }
+        Accounts { parts }
+    }
+
+    /// Returns the account of the fund called `name` below `prefix`
+    fn get(&self, prefix: &str, name: &str) -> String {
+        match self.parts.get(name) {
+            Some(part) => format!("{}:{}", prefix, part),
+            None => format!("{}:{}", prefix, name),
+        }
+    }
+}
+
+/// Returns a journal holding every fund in `funds` as an account below
+/// `prefix`, with an opening balance and an entry for every transaction
+/// in `history`. The opening balances are dated on the first transaction,
+/// or on `today` if there are none. Funds whose names would make the same
+/// account get a numbered suffix, such as `grocery-2`.
+///
+/// # Example
+/// ```
+/// extern crate chrono;
+/// extern crate libfund;
+///
+/// use chrono::NaiveDate;
+/// use libfund::export::{journal, JournalFormat};
+/// use libfund::history::{History, Transaction};
+/// use libfund::{Fund, FundManager};
+///
+/// # fn main() {
+/// let date = NaiveDate::from_ymd_opt(2026, 10, 3).unwrap();
+/// let mut funds = FundManager::new();
+/// funds.add_fund("grocery", Fund::new().with_amount(5480).build()).unwrap();
+/// let mut history = History::new();
+/// history.push(Transaction::new(date).with_leg("grocery", -4520).build());
+///
+/// let journal = journal(&funds, &history, JournalFormat::Ledger, "Assets:Funds", date);
+/// assert!(journal.contains("Assets:Funds:grocery  -45.20 USD"));
+/// assert!(journal.contains("Assets:Funds:grocery  100.00 USD"));
+/// # }
+/// ```
+pub fn journal(
+    funds: &FundManager,
+    history: &History,
+    format: JournalFormat,
+    prefix: &str,
+    today: NaiveDate,
+) -> String {
+    let currency_of = |name: &str| {
+        funds
+            .fund(name)
+            .map(|fund| fund.currency)
+            .unwrap_or_default()
+    };
+    let opened = history
+        .iter()
+        .next()
+        .map_or(today, |transaction| transaction.date);
+    let names = funds.into_iter().map(|(name, _)| name.as_str()).chain(
+        history
+            .iter()
+            .flat_map(|transaction| transaction.legs.iter().map(|leg| leg.fund.as_str())),
+    );
+    let names = Accounts::new(format, names);
+
+    let mut opening: BTreeMap<&str, i64> = funds
+        .into_iter()
+        .map(|(name, fund)| (name.as_str(), i64::from(fund.amount)))
+        .collect();
+    let mut accounts: Vec<(String, &str)> = Vec::new();
+    for (name, _) in funds {
+        accounts.push((names.get(prefix, name), "Asset"));
+    }
+    for transaction in history {
+        for leg in &transaction.legs {
+            if let Some(balance) = opening.get_mut(leg.fund.as_str()) {
+                *balance -= i64::from(leg.amount);
+            }
+            let account = names.get(prefix, &leg.fund);
+            if !accounts.iter().any(|existing| existing.0 == account) {
+                accounts.push((account, "Asset"));
+            }
+            if leg.amount < 0 {
+                let expenses = names.get("Expenses", &leg.fund);
+                if !accounts.iter().any(|existing| existing.0 == expenses) {
+                    accounts.push((expenses, "Expense"));
+                }
+            }
+        }
+    }
+    accounts.push((String::from(format.equity_account()), "Equity"));
+    accounts.push((String::from(INCOME_ACCOUNT), "Revenue"));
+
+    let mut journal = String::new();
+    if format == JournalFormat::Beancount {
+        let _ = writeln!(journal, "option \"title\" \"FundWarrior\"");
+        for (account, _) in &accounts {
+            let _ = writeln!(journal, "{} open {}", opened, account);
+        }
+    } else {
+        for (account, kind) in &accounts {
+            match format {
+                JournalFormat::Hledger => {
+                    let _ = writeln!(journal, "account {}  ; type: {}", account, kind);
+                }
+                _ => {
+                    let _ = writeln!(journal, "account {}", account);
+                }
+            }
+        }
+    }
+
+    for (name, balance) in opening.iter().filter(|entry| *entry.1 != 0) {
+        let currency = currency_of(name);
+        let _ = writeln!(journal);
+        let _ = writeln!(
+            journal,
+            "{}",
+            header(format, opened, "Opening balance", None, None)
+        );
+        posting(
+            &mut journal,
+            &names.get(prefix, name),
+            *balance,
+            currency,
+            None,
+        );
+        posting(
+            &mut journal,
+            format.equity_account(),
+            -*balance,
+            currency,
+            None,
+        );
+    }
+
+    for transaction in history {
+        let _ = writeln!(journal);
+        let description = description(transaction);
+        let payee = transaction.payee.as_deref();
+        let memo = transaction.memo.as_deref();
+        let _ = writeln!(
+            journal,
+            "{}",
+            header(format, transaction.date, &description, payee, memo)
+        );
+        write_postings(&mut journal, transaction, &names, prefix, &currency_of);
+    }
+    journal
+}
+
+/// Returns the first line of a transaction. The payee, when there is one,
+/// takes the place of the description. Beancount gets the memo as its
+/// narration, the other formats as a comment.
+fn header(
+    format: JournalFormat,
+    date: NaiveDate,
+    description: &str,
+    payee: Option<&str>,
+    memo: Option<&str>,
+) -> String {
+    match format {
+        JournalFormat::Beancount => {
+            let quote = |text: &str| format!("\"{}\"", text.replace('"', "'"));
+            match payee {
+                Some(payee) => format!(
+                    "{} * {} {}",
+                    date,
+                    quote(payee),
+                    quote(memo.unwrap_or(description))
+                ),
+                None => format!("{} * {}", date, quote(memo.unwrap_or(description))),
+            }
+        }
+        _ => {
+            let mut line = format!("{} {}", date, payee.unwrap_or(description));
+            if let Some(memo) = memo {
+                line.push_str("  ; ");
+                line.push_str(memo);
+            }
+            line
+        }
+    }
+}
+
+/// Describes what a transaction did, e.g. `Transfer from car to grocery`
+fn description(transaction: &Transaction) -> String {
+    let names = |negative: bool| {
+        transaction
+            .legs
+            .iter()
+            .filter(|leg| (leg.amount < 0) == negative)
+            .map(|leg| leg.fund.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let description = if transaction.is_transfer() {
+        format!("Transfer from {} to {}", names(true), names(false))
+    } else if transaction.legs.iter().all(|leg| leg.amount < 0) {
+        format!("Spend from {}", names(true))
+    } else {
+        format!("Deposit to {}", names(false))
+    };
+    if transaction.import_id.is_some() {
+        description + " (imported)"
+    } else {
+        description
+    }
+}
+
+fn write_postings<F: Fn(&str) -> Currency>(
+    journal: &mut String,
+    transaction: &Transaction,
+    names: &Accounts,
+    prefix: &str,
+    currency_of: &F,
+) {
+    let legs = &transaction.legs;
+    let currency = legs
+        .first()
+        .map(|leg| currency_of(&leg.fund))
+        .unwrap_or_default();
+    let same_currency = legs.iter().all(|leg| currency_of(&leg.fund) == currency);
+
+    if transaction.is_transfer() && legs.len() == 2 && !same_currency {
+        // Priced at the total paid, so the entry balances exactly
+        let (from, to) = (&legs[0], &legs[1]);
+        let paid = (-i64::from(from.amount), currency_of(&from.fund));
+        posting(
+            journal,
+            &names.get(prefix, &from.fund),
+            i64::from(from.amount),
+            paid.1,
+            None,
+        );
+        posting(
+            journal,
+            &names.get(prefix, &to.fund),
+            i64::from(to.amount),
+            currency_of(&to.fund),
+            Some(paid),
+        );
+    } else if transaction.is_transfer() && same_currency {
+        for leg in legs {
+            posting(
+                journal,
+                &names.get(prefix, &leg.fund),
+                i64::from(leg.amount),
+                currency,
+                None,
+            );
+        }
+        let residual: i64 = legs.iter().map(|leg| i64::from(leg.amount)).sum();
+        if residual != 0 {
+            posting(journal, INCOME_ACCOUNT, -residual, currency, None);
+        }
+    } else {
+        for leg in legs {
+            let amount = i64::from(leg.amount);
+            let currency = currency_of(&leg.fund);
+            posting(
+                journal,
+                &names.get(prefix, &leg.fund),
+                amount,
+                currency,
+                None,
+            );
+            if amount < 0 {
+                posting(
+                    journal,
+                    &names.get("Expenses", &leg.fund),
+                    -amount,
+                    currency,
+                    None,
+                );
+            } else {
+                posting(journal, INCOME_ACCOUNT, -amount, currency, None);
+            }
+        }
+    }
+}
+
+/// Writes a posting of `amount` minor units to `account`, optionally with
+/// the total price it was bought at
+fn posting(
+    journal: &mut String,
+    account: &str,
+    amount: i64,
+    currency: Currency,
+    price: Option<(i64, Currency)>,
+) {
+    let _ = write!(
+        journal,
+        "    {}  {} {}",
+        account,
+        decimal(amount, currency),
+        currency
+    );
+    if let Some((price, price_currency)) = price {
+        let _ = write!(
+            journal,
+            " @@ {} {}",
+            decimal(price, price_currency),
+            price_currency
+        );
+    }
+    let _ = writeln!(journal);
+}
+
+/// Writes `amount` minor units of `currency` as a plain decimal number
+fn decimal(amount: i64, currency: Currency) -> String {
+    let sign = if amount < 0 { "-" } else { "" };
+    let divisor = 10u64.pow(currency.minor_units());
+    let amount = amount.unsigned_abs();
+    if currency.minor_units() == 0 {
+        format!("{}{}", sign, amount)
+    } else {
+        format!(
+            "{}{}.{:0width$}",
+            sign,
+            amount / divisor,
+            amount % divisor,
+            width = currency.minor_units() as usize
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decimal, journal, JournalFormat};
+    use chrono::NaiveDate;
+    use currency::Currency;
+    use history::{History, Transaction};
+    use {Fund, FundManager};
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    fn funds_and_history() -> (FundManager, History) {
+        let eur: Currency = "EUR".parse().unwrap();
+        let mut funds = FundManager::new();
+        funds
+            .add_fund("grocery", Fund::new().with_amount(10_000).build())
+            .unwrap();
+        funds
+            .add_fund("eating out", Fund::new().with_amount(2_000).build())
+            .unwrap();
+        funds
+            .add_fund(
+                "trip",
+                Fund::new().with_amount(4_630).with_currency(eur).build(),
+            )
+            .unwrap();
+        let mut history = History::new();
+        history.push(
+            Transaction::new(date(2))
+                .with_leg("grocery", -4_520)
+                .with_payee("Kroger")
+                .with_memo("weekly shop")
+                .build(),
+        );
+        history.push(
+            Transaction::new(date(3))
+                .with_leg("grocery", -1_000)
+                .with_leg("eating out", 1_000)
+                .build(),
+        );
+        history.push(
+            Transaction::new(date(4))
+                .with_leg("grocery", -5_000)
+                .with_leg("trip", 4_630)
+                .with_rate(0.926)
+                .build(),
+        );
+        history.push(Transaction::new(date(5)).with_leg("grocery", 2_000).build());
+        (funds, history)
+    }
+
+    #[test]
+    fn formats_decimals() {
+        assert_eq!(decimal(-4520, Currency::USD), "-45.20");
+        assert_eq!(decimal(5, Currency::USD), "0.05");
+        assert_eq!(decimal(1200, "JPY".parse().unwrap()), "1200");
+        assert_eq!(decimal(1, "KWD".parse().unwrap()), "0.001");
+    }
+
+    #[test]
+    fn writes_ledger_journals() {
+        let (funds, history) = funds_and_history();
+        let journal = journal(
+            &funds,
+            &history,
+            JournalFormat::Ledger,
+            "Assets:Funds",
+            date(18),
+        );
+        let expected = "\
+2026-10-02 Opening balance
+    Assets:Funds:grocery  185.20 USD
+    Equity:Opening Balances  -185.20 USD
+
+2026-10-02 Kroger  ; weekly shop
+    Assets:Funds:grocery  -45.20 USD
+    Expenses:grocery  45.20 USD
+
+2026-10-03 Transfer from grocery to eating out
+    Assets:Funds:grocery  -10.00 USD
+    Assets:Funds:eating out  10.00 USD
+
+2026-10-04 Transfer from grocery to trip
+    Assets:Funds:grocery  -50.00 USD
+    Assets:Funds:trip  46.30 EUR @@ 50.00 USD
+
+2026-10-05 Deposit to grocery
+    Assets:Funds:grocery  20.00 USD
+    Income:Deposits  -20.00 USD
+";
+        assert!(journal.starts_with("account Assets:Funds:eating out\n"));
+        assert!(journal
+            .contains("2026-10-02 Opening balance\n    Assets:Funds:eating out  10.00 USD\n"));
+        assert!(journal.ends_with(expected), "{}", journal);
+        assert!(!journal.contains("Assets:Funds:trip  46.30 EUR\n    Equity"));
+    }
+
+    #[test]
+    fn writes_beancount_account_names() {
+        let (funds, history) = funds_and_history();
+        let journal = journal(
+            &funds,
+            &history,
+            JournalFormat::Beancount,
+            "Assets:Funds",
+            date(18),
+        );
+        assert!(journal.contains("2026-10-02 open Assets:Funds:Eating-out\n"));
+        assert!(journal.contains("2026-10-02 open Expenses:Grocery\n"));
+        assert!(journal.contains("2026-10-02 * \"Kroger\" \"weekly shop\"\n"));
+        assert!(journal.contains("2026-10-03 * \"Transfer from grocery to eating out\"\n"));
+        assert!(journal.contains("    Equity:Opening-Balances  -185.20 USD\n"));
+    }
+
+    #[test]
+    fn keeps_clashing_account_names_apart() {
+        let mut funds = FundManager::new();
+        for name in &[
+            "grocery",
+            "Grocery",
+            "car repairs",
+            "car-repairs",
+            "a:b",
+            "a-b",
+        ] {
+            funds
+                .add_fund(name, Fund::new().with_amount(100).build())
+                .unwrap();
+        }
+        let mut history = History::new();
+        history.push(Transaction::new(date(2)).with_leg("Grocery", -50).build());
+        history.push(Transaction::new(date(2)).with_leg("grocery", -50).build());
+
+        let beancount = journal(
+            &funds,
+            &history,
+            JournalFormat::Beancount,
+            "Assets",
+            date(18),
+        );
+        for account in &[
+            "Assets:Grocery",
+            "Assets:Grocery-2",
+            "Assets:Car-repairs",
+            "Assets:Car-repairs-2",
+            "Expenses:Grocery",
+            "Expenses:Grocery-2",
+        ] {
+            assert!(
+                beancount.contains(&format!("open {}\n", account)),
+                "{} missing from {}",
+                account,
+                beancount
+            );
+        }
+        assert!(beancount.contains("    Assets:Grocery-2  -0.50 USD\n    Expenses:Grocery-2"));
+
+        let ledger = journal(&funds, &history, JournalFormat::Ledger, "Assets", date(18));
+        for account in &[
+            "Assets:a-b",
+            "Assets:a-b-2",
+            "Assets:car repairs",
+            "Assets:car-repairs",
+        ] {
+            assert!(
+                ledger.contains(&format!("account {}\n", account)),
+                "{}",
+                ledger
+            );
+        }
+        assert!(!ledger.contains("Assets:car-repairs-2"));
+    }
+}
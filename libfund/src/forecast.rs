@@ -0,0 +1,415 @@
+//! Projections of every fund's balance, day by day, from scheduled
+//! transactions such as rent and paychecks and from how much was spent
+//! from each fund lately.
+//!
+//! Nothing here reads the clock; the day to project from is always given,
+//! so forecasts can be checked against fixed dates.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Duration, Months, NaiveDate};
+
+use history::History;
+use FundManager;
+
+/// How many days of history the spending trend is taken from
+pub const TREND_DAYS: i64 = 90;
+
+/// The error returned when parsing an unknown recurrence
+#[derive(Debug)]
+pub struct UnknownRecurrenceError {
+    recurrence: String,
+}
+
+impl fmt::Display for UnknownRecurrenceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "unknown recurrence '{}', expected daily, weekly, biweekly, monthly, quarterly, yearly or once",
+            self.recurrence
+        )
+    }
+}
+
+impl Error for UnknownRecurrenceError {}
+
+/// How often a scheduled transaction happens
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Recurrence {
+    Once,
+    Days(u32),
+    Months(u32),
+}
+
+impl FromStr for Recurrence {
+    type Err = UnknownRecurrenceError;
+
+    fn from_str(s: &str) -> Result<Recurrence, UnknownRecurrenceError> {
+        match s {
+            "once" => Ok(Recurrence::Once),
+            "daily" => Ok(Recurrence::Days(1)),
+            "weekly" => Ok(Recurrence::Days(7)),
+            "biweekly" => Ok(Recurrence::Days(14)),
+            "monthly" => Ok(Recurrence::Months(1)),
+            "quarterly" => Ok(Recurrence::Months(3)),
+            "yearly" => Ok(Recurrence::Months(12)),
+            _ => Err(UnknownRecurrenceError {
+                recurrence: String::from(s),
+            }),
+        }
+    }
+}
+
+/// A transaction that happens on a schedule, such as rent or a paycheck
+#[derive(Debug, PartialEq, Clone)]
+pub struct Schedule {
+    pub fund: String,
+    /// The amount added to the fund each time, negative for spending
+    pub amount: i32,
+    pub recurrence: Recurrence,
+    /// The first time the transaction happens
+    pub start: NaiveDate,
+}
+
+impl Schedule {
+    /// Returns the dates on which the transaction happens after `after`,
+    /// up to and including `until`
+    ///
+    /// # Example
+    /// ```
+    /// extern crate chrono;
+    /// extern crate libfund;
+    ///
+    /// use chrono::NaiveDate;
+    /// use libfund::forecast::{Recurrence, Schedule};
+    ///
+    /// # fn main() {
+    /// let date = |month, day| NaiveDate::from_ymd_opt(2027, month, day).unwrap();
+    /// let rent = Schedule {
+    ///     fund: String::from("rent"),
+    ///     amount: -90000,
+    ///     recurrence: Recurrence::Months(1),
+    ///     start: date(1, 31),
+    /// };
+    /// assert_eq!(rent.dates(date(1, 31), date(4, 1)), vec![date(2, 28), date(3, 31)]);
+    /// # }
+    /// ```
+    pub fn dates(&self, after: NaiveDate, until: NaiveDate) -> Vec<NaiveDate> {
+        let mut dates = Vec::new();
+        for occurrence in 0u32.. {
+            // Each date is counted from the start so that months with
+            // fewer days don't move the following dates
+            let date = match self.recurrence {
+                Recurrence::Once if occurrence > 0 => None,
+                Recurrence::Once => Some(self.start),
+                Recurrence::Days(days) => self
+                    .start
+                    .checked_add_signed(Duration::days(i64::from(days) * i64::from(occurrence))),
+                Recurrence::Months(months) => months
+                    .checked_mul(occurrence)
+                    .and_then(|months| self.start.checked_add_months(Months::new(months))),
+            };
+            match date {
+                Some(date) if date <= until => {
+                    if date > after {
+                        dates.push(date);
+                    }
+                }
+                _ => break,
+            }
+        }
+        dates
+    }
+}
+
+/// The projected balances of one fund
+#[derive(Debug, PartialEq, Clone)]
+pub struct FundForecast {
+    pub name: String,
+    /// The day the forecast starts from, whose balance is the fund's amount
+    pub today: NaiveDate,
+    /// The balance at the end of each day, starting with `today`
+    pub balances: Vec<i64>,
+    /// How much is expected to be spent each day besides what is scheduled
+    pub daily_spending: f64,
+    /// The first day the balance is below zero
+    pub negative_on: Option<NaiveDate>,
+    /// The first day the balance reaches the goal, if it is below it today
+    pub goal_on: Option<NaiveDate>,
+}
+
+impl FundForecast {
+    /// Returns the projected balance at the end of `date`
+    pub fn balance_on(&self, date: NaiveDate) -> Option<i64> {
+        let index = date.signed_duration_since(self.today).num_days();
+        if index < 0 {
+            return None;
+        }
+        self.balances.get(index as usize).cloned()
+    }
+
+    /// Returns the balance on the last day of the forecast
+    pub fn last(&self) -> i64 {
+        self.balances.last().cloned().unwrap_or_default()
+    }
+}
+
+/// Projects the balance of every fund from `today` until `until`, in
+/// order of their names. Each fund's balance changes by its `schedules`,
+/// and by its average daily spending over the last `TREND_DAYS` days,
+/// ignoring transfers. Funds with scheduled spending are projected from
+/// their schedules alone, so the same bills are not counted twice.
+///
+/// # Example
+/// ```
+/// extern crate chrono;
+/// extern crate libfund;
+///
+/// use chrono::NaiveDate;
+/// use libfund::forecast::{self, Recurrence, Schedule};
+/// use libfund::history::History;
+/// use libfund::{Fund, FundManager};
+///
+/// # fn main() {
+/// let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+/// let mut funds = FundManager::new();
+/// funds.add_fund("rent", Fund::new().with_amount(50000).with_goal(90000).build()).unwrap();
+/// let paycheck = Schedule {
+///     fund: String::from("rent"),
+///     amount: 20000,
+///     recurrence: Recurrence::Days(14),
+///     start: NaiveDate::from_ymd_opt(2026, 10, 23).unwrap(),
+/// };
+/// let until = NaiveDate::from_ymd_opt(2026, 12, 31).unwrap();
+/// let forecasts = forecast::forecast(&funds, &History::new(), &[paycheck], today, until);
+/// assert_eq!(forecasts[0].goal_on, NaiveDate::from_ymd_opt(2026, 11, 6));
+/// # }
+/// ```
+pub fn forecast(
+    funds: &FundManager,
+    history: &History,
+    schedules: &[Schedule],
+    today: NaiveDate,
+    until: NaiveDate,
+) -> Vec<FundForecast> {
+    let days = until.signed_duration_since(today).num_days().max(0);
+    let mut forecasts = Vec::new();
+    for (name, fund) in funds {
+        let schedules: Vec<&Schedule> = schedules
+            .iter()
+            .filter(|schedule| &schedule.fund == name)
+            .collect();
+        let daily_spending = if schedules.iter().any(|schedule| schedule.amount < 0) {
+            0.0
+        } else {
+            daily_spending(name, history, today)
+        };
+        let mut changes = vec![0i64; days as usize + 1];
+        for schedule in &schedules {
+            for date in schedule.dates(today, until) {
+                changes[date.signed_duration_since(today).num_days() as usize] +=
+                    i64::from(schedule.amount);
+            }
+        }
+
+        let mut forecast = FundForecast {
+            name: name.clone(),
+            today,
+            balances: Vec::with_capacity(changes.len()),
+            daily_spending,
+            negative_on: None,
+            goal_on: None,
+        };
+        let mut scheduled = i64::from(fund.amount);
+        for (day, change) in changes.into_iter().enumerate() {
+            scheduled += change;
+            let balance = scheduled - (daily_spending * day as f64).round() as i64;
+            let date = today + Duration::days(day as i64);
+            if balance < 0 && forecast.negative_on.is_none() {
+                forecast.negative_on = Some(date);
+            }
+            if fund.goal > 0
+                && fund.amount < fund.goal
+                && balance >= i64::from(fund.goal)
+                && forecast.goal_on.is_none()
+            {
+                forecast.goal_on = Some(date);
+            }
+            forecast.balances.push(balance);
+        }
+        forecasts.push(forecast);
+    }
+    forecasts
+}
+
+/// Returns how much was spent from the fund called `name` per day over
+/// the last `TREND_DAYS` days, or since its first transaction if that is
+/// more recent
+fn daily_spending(name: &str, history: &History, today: NaiveDate) -> f64 {
+    let from = today - Duration::days(TREND_DAYS - 1);
+    let mut first = None;
+    let mut spent = 0i64;
+    for transaction in history.iter() {
+        if transaction.date < from || transaction.date > today || transaction.is_transfer() {
+            continue;
+        }
+        for leg in transaction.legs.iter().filter(|leg| leg.fund == name) {
+            first = Some(first.map_or(transaction.date, |first: NaiveDate| {
+                first.min(transaction.date)
+            }));
+            if leg.amount < 0 {
+                spent -= i64::from(leg.amount);
+            }
+        }
+    }
+    match first {
+        Some(first) => spent as f64 / (today.signed_duration_since(first).num_days() + 1) as f64,
+        None => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{forecast, Recurrence, Schedule};
+    use chrono::NaiveDate;
+    use history::{History, Transaction};
+    use {Fund, FundManager};
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    #[test]
+    fn parses_recurrences() {
+        assert_eq!(
+            "biweekly".parse::<Recurrence>().unwrap(),
+            Recurrence::Days(14)
+        );
+        assert_eq!(
+            "quarterly".parse::<Recurrence>().unwrap(),
+            Recurrence::Months(3)
+        );
+        assert!("fortnightly".parse::<Recurrence>().is_err());
+    }
+
+    #[test]
+    fn lists_scheduled_dates_after_today() {
+        let schedule = |recurrence| Schedule {
+            fund: String::from("gym"),
+            amount: -4000,
+            recurrence,
+            start: date(9, 1),
+        };
+        assert_eq!(
+            schedule(Recurrence::Days(14)).dates(date(9, 15), date(10, 13)),
+            vec![date(9, 29), date(10, 13)]
+        );
+        assert_eq!(
+            schedule(Recurrence::Months(1)).dates(date(10, 18), date(11, 30)),
+            vec![date(11, 1)]
+        );
+        assert!(schedule(Recurrence::Once)
+            .dates(date(10, 18), date(12, 31))
+            .is_empty());
+        assert_eq!(
+            schedule(Recurrence::Once).dates(date(8, 1), date(12, 31)),
+            vec![date(9, 1)]
+        );
+    }
+
+    #[test]
+    fn projects_spending_trend_until_negative() {
+        let today = date(10, 18);
+        let mut funds = FundManager::new();
+        funds
+            .add_fund(
+                "grocery",
+                Fund::new().with_amount(10000).with_goal(20000).build(),
+            )
+            .unwrap();
+        funds
+            .add_fund("savings", Fund::new().with_amount(0).build())
+            .unwrap();
+        let mut history = History::new();
+        // $100 spent over the ten days since the first transaction
+        history.push(
+            Transaction::new(date(10, 9))
+                .with_leg("grocery", 20000)
+                .build(),
+        );
+        history.push(
+            Transaction::new(date(10, 12))
+                .with_leg("grocery", -6000)
+                .build(),
+        );
+        history.push(
+            Transaction::new(date(10, 16))
+                .with_leg("grocery", -4000)
+                .build(),
+        );
+        history.push(
+            Transaction::new(date(10, 17))
+                .with_leg("grocery", -5000)
+                .with_leg("savings", 5000)
+                .build(),
+        );
+        history.push(
+            Transaction::new(date(10, 19))
+                .with_leg("grocery", -90000)
+                .build(),
+        );
+
+        let forecasts = forecast(&funds, &history, &[], today, date(11, 30));
+        let grocery = &forecasts[0];
+        assert_eq!(grocery.daily_spending, 1000.0);
+        assert_eq!(grocery.balances[0], 10000);
+        assert_eq!(grocery.balance_on(date(10, 28)), Some(0));
+        assert_eq!(grocery.negative_on, Some(date(10, 29)));
+        assert_eq!(grocery.goal_on, None);
+        assert_eq!(grocery.balances.len(), 44);
+        assert_eq!(forecasts[1].last(), 0);
+        assert_eq!(forecasts[1].negative_on, None);
+    }
+
+    #[test]
+    fn scheduled_spending_replaces_the_trend() {
+        let today = date(10, 18);
+        let mut funds = FundManager::new();
+        funds
+            .add_fund(
+                "rent",
+                Fund::new().with_amount(100000).with_goal(90000).build(),
+            )
+            .unwrap();
+        let mut history = History::new();
+        history.push(
+            Transaction::new(date(10, 1))
+                .with_leg("rent", -90000)
+                .build(),
+        );
+        let schedules = [
+            Schedule {
+                fund: String::from("rent"),
+                amount: -90000,
+                recurrence: Recurrence::Months(1),
+                start: date(11, 1),
+            },
+            Schedule {
+                fund: String::from("rent"),
+                amount: 45000,
+                recurrence: Recurrence::Days(14),
+                start: date(10, 23),
+            },
+        ];
+
+        let rent = &forecast(&funds, &history, &schedules, today, date(12, 31))[0];
+        assert_eq!(rent.daily_spending, 0.0);
+        assert_eq!(rent.balance_on(date(10, 31)), Some(145000));
+        assert_eq!(rent.balance_on(date(11, 1)), Some(55000));
+        assert_eq!(rent.negative_on, None);
+        assert_eq!(rent.goal_on, None);
+        assert_eq!(rent.last(), 100000 - 2 * 90000 + 5 * 45000);
+    }
+}
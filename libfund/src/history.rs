@@ -0,0 +1,390 @@
+//! A record of the transactions that moved money in and out of funds.
+
+use std::fs;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::io::prelude::*;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+use std::slice::Iter;
+
+use chrono::NaiveDate;
+
+/// The change a transaction made to a single fund
+#[derive(Debug, PartialEq, Clone)]
+pub struct Leg {
+    pub fund: String,
+    /// The change in the fund's amount: negative for money leaving the
+    /// fund, positive for money entering it
+    pub amount: i32,
+}
+
+/// One or more changes to funds that happened together, such as spending
+/// from a fund or transferring money between two funds
+#[derive(Debug, PartialEq, Clone)]
+pub struct Transaction {
+    pub date: NaiveDate,
+    pub legs: Vec<Leg>,
+    /// The exchange rate used when the legs are in different currencies
+    pub rate: Option<f64>,
+    /// Identifies the statement entry the transaction was imported from,
+    /// so the same statement is never imported twice
+    pub import_id: Option<String>,
+    /// Who the money was paid to or received from
+    pub payee: Option<String>,
+    /// A note about what the transaction was for
+    pub memo: Option<String>,
+}
+
+impl Transaction {
+    /// Returns a new transaction on `date` without any legs
+    pub fn new(date: NaiveDate) -> Transaction {
+        Transaction {
+            date,
+            legs: Vec::new(),
+            rate: None,
+            import_id: None,
+            payee: None,
+            memo: None,
+        }
+    }
+
+    /// Adds a leg changing `fund` by `amount` and returns a reference to
+    /// itself. Intended for use as part of a builder pattern.
+    ///
+    /// # Example
+    /// ```
+    /// extern crate chrono;
+    /// extern crate libfund;
+    ///
+    /// use chrono::NaiveDate;
+    /// use libfund::history::Transaction;
+    ///
+    /// # fn main() {
+    /// let date = NaiveDate::from_ymd_opt(2026, 10, 1).unwrap();
+    /// let transfer = Transaction::new(date)
+    ///     .with_leg("car", -5000)
+    ///     .with_leg("grocery", 5000)
+    ///     .build();
+    /// assert!(transfer.is_transfer());
+    /// # }
+    /// ```
+    pub fn with_leg(&mut self, fund: &str, amount: i32) -> &mut Self {
+        self.legs.push(Leg {
+            fund: String::from(fund),
+            amount,
+        });
+        self
+    }
+
+    /// Sets the exchange rate used by the transaction and returns a
+    /// reference to itself. Intended for use as part of a builder pattern.
+    pub fn with_rate(&mut self, rate: f64) -> &mut Self {
+        self.rate = Some(rate);
+        self
+    }
+
+    /// Sets the id of the statement entry the transaction was imported
+    /// from and returns a reference to itself. Intended for use as part
+    /// of a builder pattern.
+    pub fn with_import_id(&mut self, import_id: &str) -> &mut Self {
+        self.import_id = Some(String::from(import_id));
+        self
+    }
+
+    /// Sets who the money was paid to or received from and returns a
+    /// reference to itself. Intended for use as part of a builder pattern.
+    pub fn with_payee(&mut self, payee: &str) -> &mut Self {
+        self.payee = Some(String::from(payee));
+        self
+    }
+
+    /// Sets a note about what the transaction was for and returns a
+    /// reference to itself. Intended for use as part of a builder pattern.
+    pub fn with_memo(&mut self, memo: &str) -> &mut Self {
+        self.memo = Some(String::from(memo));
+        self
+    }
+
+    /// Returns a new transaction based on itself. Intended as the last
+    /// step of a builder pattern.
+    pub fn build(&self) -> Transaction {
+        self.clone()
+    }
+
+    /// Returns true if money moved between funds rather than entering or
+    /// leaving them, i.e. some legs are negative and some positive
+    pub fn is_transfer(&self) -> bool {
+        self.legs.iter().any(|leg| leg.amount < 0) && self.legs.iter().any(|leg| leg.amount > 0)
+    }
+}
+
+/// Every transaction recorded for a set of funds, oldest first
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct History {
+    transactions: Vec<Transaction>,
+}
+
+impl History {
+    pub fn new() -> History {
+        History {
+            transactions: Vec::new(),
+        }
+    }
+
+    /// Returns the history stored in `historyfile`, or an empty history
+    /// if the file does not exist yet
+    ///
+    /// # Errors
+    ///
+    /// * When the file could not be read
+    /// * When the file could not be parsed correctly
+    pub fn load(historyfile: &Path) -> Result<History, io::Error> {
+        let mut history = History::new();
+        let file = match File::open(historyfile) {
+            Ok(file) => file,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(history),
+            Err(e) => return Err(e),
+        };
+        for line in BufReader::new(file).lines() {
+            match parse_transaction(&line?) {
+                Some(transaction) => history.push(transaction),
+                None => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("{:?} is invalid", historyfile),
+                    ))
+                }
+            }
+        }
+        Ok(history)
+    }
+
+    /// Saves the history to `historyfile`
+    ///
+    /// # Errors
+    ///
+    /// * When the file or its directory could not be created or written to
+    pub fn save(&self, historyfile: &Path) -> Result<(), io::Error> {
+        fs::create_dir_all(historyfile.parent().unwrap_or(historyfile))?;
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(historyfile)?;
+        let mut buf_writer = BufWriter::new(file);
+        for transaction in &self.transactions {
+            writeln!(buf_writer, "{}", format_transaction(transaction))?;
+        }
+        Ok(())
+    }
+
+    /// Records a transaction, keeping the history sorted by date
+    pub fn push(&mut self, transaction: Transaction) {
+        let index = self
+            .transactions
+            .iter()
+            .rposition(|existing| existing.date <= transaction.date)
+            .map_or(0, |index| index + 1);
+        self.transactions.insert(index, transaction);
+    }
+
+    /// Makes every leg recorded against `old_name` refer to `new_name`
+    pub fn rename_fund(&mut self, old_name: &str, new_name: &str) {
+        for transaction in &mut self.transactions {
+            for leg in &mut transaction.legs {
+                if leg.fund == old_name {
+                    leg.fund = String::from(new_name);
+                }
+            }
+        }
+    }
+
+    /// Returns true if a transaction imported from the statement entry
+    /// identified by `import_id` has been recorded
+    pub fn contains_import(&self, import_id: &str) -> bool {
+        self.transactions.iter().any(|transaction| {
+            transaction
+                .import_id
+                .as_ref()
+                .is_some_and(|id| id == import_id)
+        })
+    }
+
+    /// Returns the number of recorded transactions
+    pub fn len(&self) -> usize {
+        self.transactions.len()
+    }
+
+    /// Returns true if no transactions have been recorded
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+
+    /// Returns an iterator over the transactions, oldest first
+    pub fn iter(&self) -> Iter<'_, Transaction> {
+        self.transactions.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a History {
+    type Item = &'a Transaction;
+    type IntoIter = Iter<'a, Transaction>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.transactions.iter()
+    }
+}
+
+/// Writes a transaction as `date:fund=amount,fund=amount:rate`, followed
+/// by `:import_id` for imported transactions
+fn format_transaction(transaction: &Transaction) -> String {
+    let legs: Vec<String> = transaction
+        .legs
+        .iter()
+        .map(|leg| format!("{}={}", escape(&leg.fund), leg.amount))
+        .collect();
+    let rate = transaction
+        .rate
+        .map(|rate| rate.to_string())
+        .unwrap_or_default();
+    let mut line = format!(
+        "{}:{}:{}",
+        transaction.date.format("%Y-%m-%d"),
+        legs.join(","),
+        rate
+    );
+    // Optional fields are only written up to the last one that is set
+    let optional = [
+        &transaction.import_id,
+        &transaction.payee,
+        &transaction.memo,
+    ];
+    let count = optional
+        .iter()
+        .rposition(|field| field.is_some())
+        .map_or(0, |index| index + 1);
+    for field in &optional[..count] {
+        line.push(':');
+        if let Some(ref text) = **field {
+            line.push_str(&escape(text));
+        }
+    }
+    line
+}
+
+fn parse_transaction(line: &str) -> Option<Transaction> {
+    let fields: Vec<&str> = line.split(':').collect();
+    if fields.len() < 3 {
+        return None;
+    }
+    let mut transaction = Transaction::new(NaiveDate::parse_from_str(fields[0], "%Y-%m-%d").ok()?);
+    for leg in fields[1].split(',').filter(|leg| !leg.is_empty()) {
+        let mut parts = leg.splitn(2, '=');
+        let fund = unescape(parts.next()?)?;
+        let amount = parts.next()?.parse().ok()?;
+        transaction.with_leg(&fund, amount);
+    }
+    if !fields[2].is_empty() {
+        transaction.with_rate(fields[2].parse().ok()?);
+    }
+    let optional = |index: usize| fields.get(index).filter(|field| !field.is_empty());
+    if let Some(import_id) = optional(3) {
+        transaction.with_import_id(&unescape(import_id)?);
+    }
+    if let Some(payee) = optional(4) {
+        transaction.with_payee(&unescape(payee)?);
+    }
+    if let Some(memo) = optional(5) {
+        transaction.with_memo(&unescape(memo)?);
+    }
+    Some(transaction)
+}
+
+/// Percent-encodes the characters used as separators in the history file
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '%' | ':' | ',' | '=' | '\n' | '\r' => escaped.push_str(&format!("%{:02X}", c as u8)),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape(text: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut iter = text.bytes();
+    while let Some(byte) = iter.next() {
+        if byte == b'%' {
+            let hex = [iter.next()?, iter.next()?];
+            let hex = std::str::from_utf8(&hex).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_transaction, parse_transaction, History, Transaction};
+    use chrono::NaiveDate;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    #[test]
+    fn round_trips_transactions() {
+        let transaction = Transaction::new(date(1))
+            .with_leg("odd:name,=%", -1080)
+            .with_leg("savings", 1000)
+            .with_rate(0.925_925)
+            .build();
+        let line = format_transaction(&transaction);
+        assert_eq!(
+            line,
+            "2026-10-01:odd%3Aname%2C%3D%25=-1080,savings=1000:0.925925"
+        );
+        assert_eq!(parse_transaction(&line), Some(transaction));
+        assert_eq!(parse_transaction("2026-10-01:grocery=x:"), None);
+
+        let imported = Transaction::new(date(2))
+            .with_leg("grocery", -450)
+            .with_import_id("ofx:2026:1")
+            .build();
+        let line = format_transaction(&imported);
+        assert_eq!(line, "2026-10-02:grocery=-450::ofx%3A2026%3A1");
+        assert_eq!(parse_transaction(&line), Some(imported));
+
+        let described = Transaction::new(date(3))
+            .with_leg("grocery", -1250)
+            .with_payee("Trader Joe's")
+            .with_memo("milk: 2%")
+            .build();
+        let line = format_transaction(&described);
+        assert_eq!(line, "2026-10-03:grocery=-1250:::Trader Joe's:milk%3A 2%25");
+        assert_eq!(parse_transaction(&line), Some(described));
+    }
+
+    #[test]
+    fn keeps_transactions_in_date_order() {
+        let mut history = History::new();
+        history.push(Transaction::new(date(5)).with_leg("a", 1).build());
+        history.push(Transaction::new(date(1)).with_leg("b", 2).build());
+        history.push(Transaction::new(date(5)).with_leg("c", 3).build());
+        let funds: Vec<&str> = history.iter().map(|t| t.legs[0].fund.as_str()).collect();
+        assert_eq!(funds, vec!["b", "a", "c"]);
+    }
+
+    #[test]
+    fn renames_legs() {
+        let mut history = History::new();
+        history.push(Transaction::new(date(1)).with_leg("old", -5).build());
+        history.rename_fund("old", "new");
+        assert_eq!(history.iter().next().unwrap().legs[0].fund, "new");
+    }
+}
@@ -0,0 +1,856 @@
+//! Reading bank statements and posting their entries to funds.
+//!
+//! Statements are parsed into `StatementEntry`s, which `Rules` assign to
+//! funds by matching their descriptions. Every entry carries an id that
+//! is stored in the `History` when it is recorded, so importing the same
+//! statement twice does not post anything the second time.
+
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io;
+use std::io::prelude::*;
+use std::io::BufReader;
+use std::path::Path;
+
+use chrono::NaiveDate;
+use regex::{Regex, RegexBuilder};
+
+use history::{History, Transaction};
+use {FundManager, FundNotFoundError};
+
+/// The error returned when a statement could not be parsed
+#[derive(Debug)]
+pub struct ImportError {
+    line: usize,
+    message: String,
+}
+
+impl ImportError {
+    fn new(line: usize, message: &str) -> ImportError {
+        ImportError {
+            line,
+            message: String::from(message),
+        }
+    }
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for ImportError {}
+
+/// A single line of a bank statement
+#[derive(Debug, PartialEq, Clone)]
+pub struct StatementEntry {
+    pub date: NaiveDate,
+    /// The amount in minor units: negative for money leaving the account,
+    /// positive for money entering it
+    pub amount: i32,
+    pub description: String,
+    /// Identifies the entry across imports of the same statement
+    pub id: String,
+}
+
+/// Refers to a column of a CSV statement, either by its 1-based position
+/// or by its name in the header row
+#[derive(Debug, PartialEq, Clone)]
+pub enum ColumnRef {
+    Index(usize),
+    Name(String),
+}
+
+impl<'a> From<&'a str> for ColumnRef {
+    fn from(s: &'a str) -> ColumnRef {
+        match s.trim().parse::<usize>() {
+            Ok(index) if index > 0 => ColumnRef::Index(index - 1),
+            _ => ColumnRef::Name(String::from(s.trim())),
+        }
+    }
+}
+
+impl ColumnRef {
+    fn resolve(&self, header: Option<&[String]>) -> Option<usize> {
+        match *self {
+            ColumnRef::Index(index) => Some(index),
+            ColumnRef::Name(ref name) => header?
+                .iter()
+                .position(|column| column.trim().eq_ignore_ascii_case(name)),
+        }
+    }
+}
+
+/// Describes how a bank lays out its CSV statements
+#[derive(Debug, PartialEq, Clone)]
+pub struct CsvProfile {
+    pub delimiter: char,
+    /// How many lines to skip before the header or first entry
+    pub skip: usize,
+    /// Whether the first line after the skipped ones names the columns
+    pub header: bool,
+    pub date: ColumnRef,
+    /// The `chrono` format of dates, e.g. `%m/%d/%Y`
+    pub date_format: String,
+    /// A column holding signed amounts. Ignored when `debit` or `credit`
+    /// is set.
+    pub amount: Option<ColumnRef>,
+    /// A column holding money leaving the account
+    pub debit: Option<ColumnRef>,
+    /// A column holding money entering the account
+    pub credit: Option<ColumnRef>,
+    pub description: ColumnRef,
+    /// A column holding the bank's own id for each entry, if it has one
+    pub id: Option<ColumnRef>,
+    /// Whether positive amounts are money leaving the account, as on many
+    /// credit card statements
+    pub inverted: bool,
+    pub decimal_separator: char,
+    /// How many digits amounts have after the decimal separator
+    pub decimals: u32,
+}
+
+impl Default for CsvProfile {
+    fn default() -> CsvProfile {
+        CsvProfile::new()
+    }
+}
+
+impl CsvProfile {
+    /// Returns a profile for comma-separated statements with a header row
+    /// and `Date`, `Amount` and `Description` columns
+    pub fn new() -> CsvProfile {
+        CsvProfile {
+            delimiter: ',',
+            skip: 0,
+            header: true,
+            date: ColumnRef::from("Date"),
+            date_format: String::from("%Y-%m-%d"),
+            amount: Some(ColumnRef::from("Amount")),
+            debit: None,
+            credit: None,
+            description: ColumnRef::from("Description"),
+            id: None,
+            inverted: false,
+            decimal_separator: '.',
+            decimals: 2,
+        }
+    }
+
+    /// Parses the entries of a CSV statement. Blank lines are skipped.
+    ///
+    /// # Errors
+    ///
+    /// * When a column named by the profile does not exist
+    /// * When a date or amount could not be parsed
+    ///
+    /// # Example
+    /// ```
+    /// use libfund::import::CsvProfile;
+    ///
+    /// let statement = "Date,Description,Amount\n2026-10-03,KROGER #123,-45.20\n";
+    /// let entries = CsvProfile::new().parse(statement).unwrap();
+    /// assert_eq!(entries[0].amount, -4520);
+    /// assert_eq!(entries[0].description, "KROGER #123");
+    /// ```
+    pub fn parse(&self, text: &str) -> Result<Vec<StatementEntry>, ImportError> {
+        let mut records = csv_records(text, self.delimiter)
+            .into_iter()
+            .skip(self.skip);
+        let header = if self.header { records.next() } else { None };
+        let header = header.as_ref().map(|record| record.1.as_slice());
+        let header_line = if self.header {
+            self.skip + 1
+        } else {
+            self.skip
+        };
+
+        let column = |column: &ColumnRef| {
+            column.resolve(header).ok_or_else(|| {
+                ImportError::new(
+                    header_line,
+                    &format!("no column called {:?}", column_name(column)),
+                )
+            })
+        };
+        let date = column(&self.date)?;
+        let description = column(&self.description)?;
+        let id = self.id.as_ref().map(&column).transpose()?;
+        let (debit, credit) = (
+            self.debit.as_ref().map(&column).transpose()?,
+            self.credit.as_ref().map(&column).transpose()?,
+        );
+        let amount = match (debit, credit, self.amount.as_ref()) {
+            (None, None, Some(amount)) => Some(column(amount)?),
+            (None, None, None) => {
+                return Err(ImportError::new(
+                    header_line,
+                    "the profile names no amount column",
+                ))
+            }
+            _ => None,
+        };
+
+        let mut entries: Vec<StatementEntry> = Vec::new();
+        for (line, record) in records {
+            if record.iter().all(|field| field.trim().is_empty()) {
+                continue;
+            }
+            let field = |index: usize| record.get(index).map_or("", |field| field.trim());
+            let entry_date = NaiveDate::parse_from_str(field(date), &self.date_format)
+                .map_err(|_| ImportError::new(line, &format!("invalid date {:?}", field(date))))?;
+            let parse = |index: usize| -> Result<i64, ImportError> {
+                if field(index).is_empty() {
+                    return Ok(0);
+                }
+                parse_amount(field(index), self.decimal_separator, self.decimals).ok_or_else(|| {
+                    ImportError::new(line, &format!("invalid amount {:?}", field(index)))
+                })
+            };
+            let mut entry_amount = match amount {
+                Some(amount) => parse(amount)?,
+                None => credit.map_or(Ok(0), &parse)?.abs() - debit.map_or(Ok(0), &parse)?.abs(),
+            };
+            if self.inverted {
+                entry_amount = -entry_amount;
+            }
+            let entry_amount = i32::try_from(entry_amount)
+                .map_err(|_| ImportError::new(line, "amount is too large"))?;
+            let entry_description = String::from(field(description));
+            let entry_id = match id {
+                Some(id) if !field(id).is_empty() => format!("csv:{}", field(id)),
+                _ => content_id(
+                    "csv",
+                    &entries,
+                    entry_date,
+                    entry_amount,
+                    &entry_description,
+                ),
+            };
+            entries.push(StatementEntry {
+                date: entry_date,
+                amount: entry_amount,
+                description: entry_description,
+                id: entry_id,
+            });
+        }
+        Ok(entries)
+    }
+}
+
+/// Parses the transactions of an OFX or QFX statement, in either the
+/// SGML format of OFX 1.x, where closing tags are optional, or the XML
+/// format of OFX 2.x. Entries are identified by their account and FITID.
+/// Amounts are read into minor units of a currency with `decimals` digits
+/// after the decimal point.
+///
+/// # Errors
+///
+/// * When a transaction's date or amount is missing or invalid, or the
+///   amount has more decimals than `decimals`
+///
+/// # Example
+/// ```
+/// use libfund::import::parse_ofx;
+///
+/// let statement = "OFXHEADER:100\n<OFX><BANKTRANLIST>\n<STMTTRN>\n<TRNTYPE>DEBIT\n\
+///                  <DTPOSTED>20261003120000\n<TRNAMT>-45.20\n<FITID>3001\n\
+///                  <NAME>KROGER #123\n</STMTTRN>\n</BANKTRANLIST></OFX>";
+/// let entries = parse_ofx(statement, 2).unwrap();
+/// assert_eq!(entries[0].amount, -4520);
+/// assert_eq!(entries[0].id, "ofx:3001");
+/// ```
+pub fn parse_ofx(text: &str, decimals: u32) -> Result<Vec<StatementEntry>, ImportError> {
+    let mut entries: Vec<StatementEntry> = Vec::new();
+    let mut account: Option<String> = None;
+    let mut transaction: Option<(usize, Vec<(String, String)>)> = None;
+    let mut line = 1;
+    for token in text.split('<') {
+        let start = line;
+        line += token.matches('\n').count();
+        let (tag, value) = match token.find('>') {
+            Some(index) => (
+                token[..index].trim().to_uppercase(),
+                token[index + 1..].trim(),
+            ),
+            None => continue,
+        };
+        match tag.as_str() {
+            "STMTTRN" => transaction = Some((start, Vec::new())),
+            "/STMTTRN" => {
+                if let Some((start, fields)) = transaction.take() {
+                    entries.push(ofx_entry(
+                        start,
+                        &fields,
+                        account.as_ref(),
+                        decimals,
+                        &entries,
+                    )?);
+                }
+            }
+            "ACCTID" => account = Some(unescape_xml(value)),
+            _ if tag.starts_with('/') || tag.starts_with('?') || tag.starts_with('!') => {}
+            _ => {
+                if let Some((_, ref mut fields)) = transaction {
+                    fields.push((tag, unescape_xml(value)));
+                }
+            }
+        }
+    }
+    Ok(entries)
+}
+
+fn ofx_entry(
+    line: usize,
+    fields: &[(String, String)],
+    account: Option<&String>,
+    decimals: u32,
+    entries: &[StatementEntry],
+) -> Result<StatementEntry, ImportError> {
+    let field = |tag: &str| {
+        fields
+            .iter()
+            .find(|field| field.0 == tag && !field.1.is_empty())
+            .map(|field| field.1.as_str())
+    };
+    let posted =
+        field("DTPOSTED").ok_or_else(|| ImportError::new(line, "transaction has no date"))?;
+    let date = posted
+        .get(..8)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y%m%d").ok())
+        .ok_or_else(|| ImportError::new(line, &format!("invalid date {:?}", posted)))?;
+    let amount =
+        field("TRNAMT").ok_or_else(|| ImportError::new(line, "transaction has no amount"))?;
+    let decimal_separator = if amount.contains(',') && !amount.contains('.') {
+        ','
+    } else {
+        '.'
+    };
+    let amount = parse_amount(amount, decimal_separator, decimals)
+        .and_then(|amount| i32::try_from(amount).ok())
+        .ok_or_else(|| ImportError::new(line, &format!("invalid amount {:?}", amount)))?;
+    let description = match (field("NAME"), field("MEMO")) {
+        (Some(name), _) => String::from(name),
+        (None, Some(memo)) => String::from(memo),
+        (None, None) => String::new(),
+    };
+    let id = match (field("FITID"), account) {
+        (Some(fitid), Some(account)) => format!("ofx:{}:{}", account, fitid),
+        (Some(fitid), None) => format!("ofx:{}", fitid),
+        (None, _) => content_id("ofx", entries, date, amount, &description),
+    };
+    Ok(StatementEntry {
+        date,
+        amount,
+        description,
+        id,
+    })
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Parses the transactions of a QIF statement. QIF dates have no fixed
+/// format, so `day_first` tells whether they are written day before
+/// month. Years may have two digits, and an apostrophe before the year
+/// means the 2000s, e.g. `10/3'26`. Amounts are read into minor units of a
+/// currency with `decimals` digits after the decimal point.
+///
+/// # Errors
+///
+/// * When a transaction's date or amount is missing or invalid, or the
+///   amount has more decimals than `decimals`
+///
+/// # Example
+/// ```
+/// use libfund::import::parse_qif;
+///
+/// let statement = "!Type:Bank\nD10/03/2026\nT-45.20\nPKROGER #123\n^\n";
+/// let entries = parse_qif(statement, false, 2).unwrap();
+/// assert_eq!(entries[0].amount, -4520);
+/// assert_eq!(entries[0].description, "KROGER #123");
+/// ```
+pub fn parse_qif(
+    text: &str,
+    day_first: bool,
+    decimals: u32,
+) -> Result<Vec<StatementEntry>, ImportError> {
+    let mut entries: Vec<StatementEntry> = Vec::new();
+    let mut start = 1;
+    let mut date = None;
+    let mut amount = None;
+    let mut payee: Option<String> = None;
+    let mut memo: Option<String> = None;
+    for (index, line) in text.lines().enumerate() {
+        let number = index + 1;
+        let line = line.trim();
+        let mut chars = line.chars();
+        let code = chars.next();
+        let value = chars.as_str().trim();
+        match code {
+            None | Some('!') => continue,
+            Some('^') => {
+                let date = date
+                    .take()
+                    .ok_or_else(|| ImportError::new(start, "transaction has no date"))?;
+                let amount = amount
+                    .take()
+                    .ok_or_else(|| ImportError::new(start, "transaction has no amount"))?;
+                let description = payee.take().or_else(|| memo.take()).unwrap_or_default();
+                let id = content_id("qif", &entries, date, amount, &description);
+                entries.push(StatementEntry {
+                    date,
+                    amount,
+                    description,
+                    id,
+                });
+                memo = None;
+                start = number + 1;
+                continue;
+            }
+            Some('D') => {
+                date = Some(parse_qif_date(value, day_first).ok_or_else(|| {
+                    ImportError::new(number, &format!("invalid date {:?}", value))
+                })?)
+            }
+            Some('T') | Some('U') => {
+                amount = Some(
+                    parse_amount(value, '.', decimals)
+                        .and_then(|amount| i32::try_from(amount).ok())
+                        .ok_or_else(|| {
+                            ImportError::new(number, &format!("invalid amount {:?}", value))
+                        })?,
+                )
+            }
+            Some('P') => payee = Some(String::from(value)),
+            Some('M') => memo = Some(String::from(value)),
+            _ => {}
+        }
+    }
+    Ok(entries)
+}
+
+fn parse_qif_date(text: &str, day_first: bool) -> Option<NaiveDate> {
+    let apostrophe = text.contains('\'');
+    let parts: Vec<u32> = text
+        .split(['/', '.', '-', '\''])
+        .map(|part| part.trim().parse().ok())
+        .collect::<Option<Vec<u32>>>()?;
+    if parts.len() != 3 {
+        return None;
+    }
+    let (month, day) = if day_first {
+        (parts[1], parts[0])
+    } else {
+        (parts[0], parts[1])
+    };
+    let year = match parts[2] {
+        year if year >= 100 => year,
+        year if apostrophe || year < 70 => 2000 + year,
+        year => 1900 + year,
+    };
+    NaiveDate::from_ymd_opt(year as i32, month, day)
+}
+
+fn column_name(column: &ColumnRef) -> String {
+    match *column {
+        ColumnRef::Index(index) => (index + 1).to_string(),
+        ColumnRef::Name(ref name) => name.clone(),
+    }
+}
+
+/// Splits CSV text into records, each paired with the line it starts on.
+/// Fields may be quoted, in which case they can hold the delimiter,
+/// newlines and doubled quotes.
+fn csv_records(text: &str, delimiter: char) -> Vec<(usize, Vec<String>)> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut line = 1;
+    let mut start = 1;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\n' {
+            line += 1;
+        }
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                quoted = true;
+            }
+            c if quoted => field.push(c),
+            c if c == delimiter => record.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                records.push((start, std::mem::take(&mut record)));
+                start = line;
+            }
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push((start, record));
+    }
+    records
+}
+
+/// Parses an amount such as `-1,234.56`, `$12.00` or `(12.00)` into minor
+/// units, ignoring currency symbols and thousands separators. Zeros beyond
+/// `decimals` are dropped, so `500.00` of a currency without decimals is 500.
+fn parse_amount(text: &str, decimal_separator: char, decimals: u32) -> Option<i64> {
+    let text = text.trim();
+    let negative = text.starts_with('-')
+        || text.ends_with('-')
+        || (text.starts_with('(') && text.ends_with(')'));
+    let mut whole = String::new();
+    let mut fraction: Option<String> = None;
+    for c in text.chars() {
+        if c == decimal_separator {
+            if fraction.is_some() {
+                return None;
+            }
+            fraction = Some(String::new());
+        } else if c.is_ascii_digit() {
+            fraction.as_mut().unwrap_or(&mut whole).push(c);
+        }
+    }
+    let mut fraction = fraction.unwrap_or_default();
+    if whole.is_empty() && fraction.is_empty() {
+        return None;
+    }
+    while fraction.len() > decimals as usize && fraction.ends_with('0') {
+        fraction.pop();
+    }
+    if fraction.len() > decimals as usize {
+        return None;
+    }
+    while fraction.len() < decimals as usize {
+        fraction.push('0');
+    }
+    let amount: i64 = format!("{}{}", whole, fraction).parse().ok()?;
+    Some(if negative { -amount } else { amount })
+}
+
+/// Returns an id for an entry from a statement format without ids of its
+/// own, made from its contents and how many identical entries come before
+/// it in `entries`
+fn content_id(
+    prefix: &str,
+    entries: &[StatementEntry],
+    date: NaiveDate,
+    amount: i32,
+    description: &str,
+) -> String {
+    let repeats = entries
+        .iter()
+        .filter(|entry| {
+            entry.date == date && entry.amount == amount && entry.description == description
+        })
+        .count();
+    let key = format!("{}|{}|{}|{}", date, amount, description, repeats);
+    format!("{}:{:016x}", prefix, fnv1a(&key))
+}
+
+/// The 64-bit FNV-1a hash, used for ids that must stay the same across
+/// versions and platforms
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Assigns statement entries to funds by matching their descriptions
+/// against regular expressions, which ignore case
+#[derive(Debug, Default, Clone)]
+pub struct Rules {
+    rules: Vec<(Regex, String)>,
+}
+
+impl Rules {
+    pub fn new() -> Rules {
+        Rules { rules: Vec::new() }
+    }
+
+    /// Reads rules from `rulesfile`, one `fund:regex` pair per line. Blank
+    /// lines and lines starting with `#` are ignored. A missing file
+    /// results in no rules.
+    ///
+    /// # Errors
+    ///
+    /// * When the file exists but could not be read
+    /// * When a line is not a `fund:regex` pair or its regex is invalid
+    pub fn load(rulesfile: &Path) -> Result<Rules, io::Error> {
+        let mut rules = Rules::new();
+        let file = match File::open(rulesfile) {
+            Ok(file) => file,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(rules),
+            Err(e) => return Err(e),
+        };
+        for (number, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = |message: String| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{:?} line {}: {}", rulesfile, number + 1, message),
+                )
+            };
+            let mut parts = line.splitn(2, ':');
+            match (parts.next(), parts.next()) {
+                (Some(fund), Some(pattern)) => rules
+                    .add(fund.trim(), pattern.trim())
+                    .map_err(|e| invalid(e.to_string()))?,
+                _ => return Err(invalid(String::from("expected 'fund:regex'"))),
+            }
+        }
+        Ok(rules)
+    }
+
+    /// Adds a rule assigning entries whose description matches `pattern`
+    /// to `fund`. Earlier rules take precedence.
+    ///
+    /// # Errors
+    ///
+    /// * When `pattern` is not a valid regular expression
+    pub fn add(&mut self, fund: &str, pattern: &str) -> Result<(), regex::Error> {
+        let regex = RegexBuilder::new(pattern).case_insensitive(true).build()?;
+        self.rules.push((regex, String::from(fund)));
+        Ok(())
+    }
+
+    /// Returns the fund of the first rule matching `description`
+    ///
+    /// # Example
+    /// ```
+    /// use libfund::import::Rules;
+    ///
+    /// let mut rules = Rules::new();
+    /// rules.add("grocery", "kroger|aldi").unwrap();
+    /// assert_eq!(rules.fund_for("KROGER #123"), Some("grocery"));
+    /// assert_eq!(rules.fund_for("Shell Oil"), None);
+    /// ```
+    pub fn fund_for(&self, description: &str) -> Option<&str> {
+        self.rules
+            .iter()
+            .find(|rule| rule.0.is_match(description))
+            .map(|rule| rule.1.as_str())
+    }
+}
+
+/// Posts `entry` to the fund called `fund` and records it in `history`.
+/// Returns false without changing anything if the entry was already
+/// imported.
+///
+/// # Errors
+///
+/// * When no fund is called `fund`
+pub fn record(
+    entry: &StatementEntry,
+    fund: &str,
+    funds: &mut FundManager,
+    history: &mut History,
+) -> Result<bool, FundNotFoundError> {
+    if history.contains_import(&entry.id) {
+        return Ok(false);
+    }
+    funds.fund_mut(fund)?.deposit(entry.amount);
+    history.push(
+        Transaction::new(entry.date)
+            .with_leg(fund, entry.amount)
+            .with_import_id(&entry.id)
+            .build(),
+    );
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{csv_records, parse_amount, parse_ofx, parse_qif, record, ColumnRef, CsvProfile};
+    use chrono::NaiveDate;
+    use history::History;
+    use {Fund, FundManager};
+
+    #[test]
+    fn splits_quoted_fields() {
+        let records = csv_records("a,\"b, \"\"c\"\"\",d\r\n\"multi\nline\",e\n", ',');
+        assert_eq!(
+            records[0],
+            (1, vec!["a".into(), "b, \"c\"".into(), "d".into()])
+        );
+        assert_eq!(records[1], (2, vec!["multi\nline".into(), "e".into()]));
+        assert_eq!(records.len(), 2);
+    }
+
+    #[test]
+    fn parses_amounts() {
+        assert_eq!(parse_amount("-1,234.56", '.', 2), Some(-123_456));
+        assert_eq!(parse_amount("$12", '.', 2), Some(1200));
+        assert_eq!(parse_amount("(3.5)", '.', 2), Some(-350));
+        assert_eq!(parse_amount("1.234,5", ',', 2), Some(123_450));
+        assert_eq!(parse_amount("1.234", '.', 2), None);
+        assert_eq!(parse_amount("abc", '.', 2), None);
+        assert_eq!(parse_amount("1.50", '.', 3), Some(1500));
+        assert_eq!(parse_amount("-4520.00", '.', 0), Some(-4520));
+        assert_eq!(parse_amount("4520", '.', 0), Some(4520));
+        assert_eq!(parse_amount("45.20", '.', 0), None);
+        assert_eq!(parse_amount("0.0", '.', 2), Some(0));
+    }
+
+    #[test]
+    fn parses_debit_and_credit_columns() {
+        let mut profile = CsvProfile::new();
+        profile.header = false;
+        profile.skip = 1;
+        profile.delimiter = ';';
+        profile.date = ColumnRef::from("1");
+        profile.date_format = String::from("%d/%m/%Y");
+        profile.description = ColumnRef::from("2");
+        profile.debit = Some(ColumnRef::from("3"));
+        profile.credit = Some(ColumnRef::from("4"));
+        let statement = "Account 1234\n03/10/2026;Rent;800,00;\n04/10/2026;Salary;;2000\n";
+        profile.decimal_separator = ',';
+        let entries = profile.parse(statement).unwrap();
+        assert_eq!(entries[0].amount, -80_000);
+        assert_eq!(
+            entries[0].date,
+            NaiveDate::from_ymd_opt(2026, 10, 3).unwrap()
+        );
+        assert_eq!(entries[1].amount, 200_000);
+        assert_eq!(entries[1].description, "Salary");
+
+        let error = profile.parse("x\n03/10/2026;Rent;lots;\n").unwrap_err();
+        assert_eq!(error.to_string(), "line 2: invalid amount \"lots\"");
+    }
+
+    #[test]
+    fn gives_repeated_entries_distinct_ids() {
+        let statement = "Date,Description,Amount\n\
+                         2026-10-03,Coffee,-3.00\n\
+                         2026-10-03,Coffee,-3.00\n";
+        let entries = CsvProfile::new().parse(statement).unwrap();
+        assert_ne!(entries[0].id, entries[1].id);
+        assert_eq!(CsvProfile::new().parse(statement).unwrap(), entries);
+    }
+
+    #[test]
+    fn parses_xml_ofx() {
+        let statement = r#"<?xml version="1.0" encoding="UTF-8"?>
+<?OFX OFXHEADER="200" VERSION="220"?>
+<OFX><CREDITCARDMSGSRSV1><CCSTMTTRNRS><CCSTMTRS>
+<CCACCTFROM><ACCTID>9876</ACCTID></CCACCTFROM>
+<BANKTRANLIST>
+<STMTTRN><TRNTYPE>DEBIT</TRNTYPE><DTPOSTED>20261003</DTPOSTED>
+<TRNAMT>-12.5</TRNAMT><FITID>A1</FITID><NAME>Bob &amp; Sons</NAME></STMTTRN>
+<STMTTRN><TRNTYPE>CREDIT</TRNTYPE><DTPOSTED>20261005000000.000[-5:EST]</DTPOSTED>
+<TRNAMT>100.00</TRNAMT><FITID>A2</FITID><MEMO>Refund</MEMO></STMTTRN>
+</BANKTRANLIST></CCSTMTRS></CCSTMTTRNRS></CREDITCARDMSGSRSV1></OFX>"#;
+        let entries = parse_ofx(statement, 2).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].description, "Bob & Sons");
+        assert_eq!(entries[0].amount, -1250);
+        assert_eq!(entries[0].id, "ofx:9876:A1");
+        assert_eq!(
+            entries[1].date,
+            NaiveDate::from_ymd_opt(2026, 10, 5).unwrap()
+        );
+        assert_eq!(entries[1].description, "Refund");
+
+        let error = parse_ofx(
+            "<OFX>
+<STMTTRN>
+<DTPOSTED>20261003
+</STMTTRN>",
+            2,
+        )
+        .unwrap_err();
+        assert_eq!(error.to_string(), "line 2: transaction has no amount");
+
+        let statement = |amount| {
+            format!(
+                "<OFX><STMTTRN><DTPOSTED>20261003<TRNAMT>{}<FITID>1</STMTTRN></OFX>",
+                amount
+            )
+        };
+        assert_eq!(
+            parse_ofx(&statement("-4520.00"), 0).unwrap()[0].amount,
+            -4520
+        );
+        assert!(parse_ofx(&statement("-45.20"), 0).is_err());
+    }
+
+    #[test]
+    fn parses_qif() {
+        let statement = "!Type:CCard
+D3.10'26
+T-1,234.50
+MCoffee
+^
+                         D03.10.2026
+U-1,234.50
+PCoffee
+^
+";
+        let entries = parse_qif(statement, true, 2).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries[0].date,
+            NaiveDate::from_ymd_opt(2026, 10, 3).unwrap()
+        );
+        assert_eq!(entries[0].amount, -123_450);
+        assert_eq!(entries[0].description, "Coffee");
+        assert_ne!(entries[0].id, entries[1].id);
+        assert!(parse_qif(
+            "D13/40/2026
+^
+",
+            false,
+            2
+        )
+        .is_err());
+        let entries = parse_qif(
+            "D10/03/2026\nT-4520\n^\nD10/03/2026\nT1,000.00\n^\n",
+            false,
+            0,
+        )
+        .unwrap();
+        assert_eq!(entries[0].amount, -4520);
+        assert_eq!(entries[1].amount, 1000);
+        assert!(parse_qif("D10/03/2026\nT-45.20\n^\n", false, 0).is_err());
+    }
+
+    #[test]
+    fn records_entries_once() {
+        let mut funds = FundManager::new();
+        funds
+            .add_fund("grocery", Fund::new().with_amount(10_000).build())
+            .unwrap();
+        let mut history = History::new();
+        let statement = "Date,Description,Amount\n2026-10-03,Kroger,-45.20\n";
+        let entries = CsvProfile::new().parse(statement).unwrap();
+
+        assert!(record(&entries[0], "grocery", &mut funds, &mut history).unwrap());
+        assert!(!record(&entries[0], "grocery", &mut funds, &mut history).unwrap());
+        assert_eq!(funds.fund("grocery").unwrap().amount, 5480);
+        assert_eq!(history.len(), 1);
+        assert!(record(&entries[0], "missing", &mut funds, &mut History::new()).is_err());
+    }
+}
@@ -5,34 +5,101 @@
 //! it into a separate library to make it easier to reuse later, if
 //! I or anyone else wished to make a GUI version of FundWarrior for
 //! example.
-//! 
-//! ## Warning
-//! 
-//! The `FundManager` struct implements `Extend`, but it has a caveat.
-//! Any `Fund`s in the supplied iterator that have the same name as any
-//! existing `Fund` will be ignored.
+//!
+//! ## Merging
+//!
+//! `FundManager::merge` combines two sets of funds, settling funds with
+//! the same name as a `MergeStrategy` says and reporting what it did.
+//! The `Extend` and `FromIterator` impls behave like those of the
+//! standard maps, which is `MergeStrategy::TakeIncoming`: a fund replaces
+//! any earlier fund with the same name.
+
+extern crate chrono;
+extern crate regex;
+#[macro_use]
+extern crate serde;
+#[cfg(test)]
+extern crate proptest;
+extern crate serde_json;
+
+pub mod alerts;
+pub mod backup;
+pub mod currency;
+pub mod export;
+pub mod forecast;
+pub mod history;
+pub mod import;
+pub mod money;
+pub mod stats;
+pub mod timeseries;
 
+use std::cmp;
 use std::cmp::Ordering;
-use std::collections::hash_map::{Iter, IterMut};
-use std::collections::HashMap;
+use std::collections::btree_map::{Iter, IterMut};
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
 use std::error::Error;
 use std::fmt;
 use std::fs;
 use std::fs::OpenOptions;
+use std::io;
 use std::io::prelude::*;
 use std::io::{BufReader, BufWriter};
 use std::iter::FromIterator;
 use std::path::Path;
+use std::str::FromStr;
 
-/// The error returned when a fund could not be found
+use chrono::NaiveDate;
+
+use currency::{Currency, NoRateError, Rates};
+use history::Transaction;
+use import::ImportError;
+use money::MoneyFormat;
+
+/// The error returned when a fund could not be found, or when a name
+/// matches several funds. It suggests the funds whose names are closest to
+/// the one given, if any are close, or the funds it matches.
 #[derive(Debug)]
 pub struct FundNotFoundError {
     name: String,
+    suggestions: Vec<String>,
+    ambiguous: bool,
+}
+
+impl FundNotFoundError {
+    /// The names of the funds that might have been meant
+    pub fn suggestions(&self) -> &[String] {
+        &self.suggestions
+    }
+
+    /// Whether the name matched several funds rather than none
+    pub fn is_ambiguous(&self) -> bool {
+        self.ambiguous
+    }
 }
 
 impl fmt::Display for FundNotFoundError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "fund '{}' not found", self.name)
+        let (last, rest) = match self.suggestions.split_last() {
+            Some(split) => split,
+            None => return write!(f, "fund '{}' not found", self.name),
+        };
+        let (conjunction, end) = if self.ambiguous {
+            write!(f, "'{}' matches several funds: ", self.name)?;
+            (" and ", "")
+        } else {
+            write!(f, "fund '{}' not found, did you mean ", self.name)?;
+            (" or ", "?")
+        };
+        for (index, suggestion) in rest.iter().enumerate() {
+            let separator = if index + 1 < rest.len() {
+                ", "
+            } else {
+                conjunction
+            };
+            write!(f, "'{}'{}", suggestion, separator)?;
+        }
+        write!(f, "'{}'{}", last, end)
     }
 }
 
@@ -42,6 +109,16 @@ impl Error for FundNotFoundError {
     }
 }
 
+/// How `FundManager::resolve` matches a name given by the user to a fund
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum NameMatching {
+    /// Only the exact name matches
+    Exact,
+    /// The name matches ignoring case, or when it is the start of exactly
+    /// one fund's name
+    Loose,
+}
+
 /// The error returned when attempting to create or rename a
 /// fund to a name that already exists
 #[derive(Debug)]
@@ -65,8 +142,89 @@ impl Error for DuplicateFundError {
     }
 }
 
-/// A wrapper around FundNotFoundError, DuplicateFundError,
-/// and std::io::Error. Useful for binary crates dealing with
+/// The error returned when two funds with the same name could not be
+/// merged
+#[derive(Debug)]
+pub struct MergeError {
+    name: String,
+    reason: &'static str,
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "can't merge fund '{}': {}", self.name, self.reason)
+    }
+}
+
+impl Error for MergeError {}
+
+/// How `FundManager::merge` settles an incoming fund whose name is taken
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum MergeStrategy {
+    /// Keep the existing fund and leave out the incoming one
+    KeepExisting,
+    /// Replace the existing fund with the incoming one
+    TakeIncoming,
+    /// Add the incoming fund's amount to the existing one, keeping the
+    /// existing goal. Both funds must be in the same currency.
+    SumBalances,
+    /// Merge nothing and return an error
+    Error,
+    /// Add the incoming fund under a new name, such as `grocery-2`
+    RenameIncoming,
+}
+
+impl FromStr for MergeStrategy {
+    type Err = UnknownStrategyError;
+
+    fn from_str(s: &str) -> Result<MergeStrategy, UnknownStrategyError> {
+        match s {
+            "keep" | "skip" => Ok(MergeStrategy::KeepExisting),
+            "take" | "overwrite" => Ok(MergeStrategy::TakeIncoming),
+            "sum" => Ok(MergeStrategy::SumBalances),
+            "error" => Ok(MergeStrategy::Error),
+            "rename" => Ok(MergeStrategy::RenameIncoming),
+            _ => Err(UnknownStrategyError {
+                strategy: String::from(s),
+            }),
+        }
+    }
+}
+
+/// The error returned when parsing an unknown merge strategy
+#[derive(Debug)]
+pub struct UnknownStrategyError {
+    strategy: String,
+}
+
+impl fmt::Display for UnknownStrategyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "unknown merge strategy '{}', expected skip, overwrite, sum, error or rename",
+            self.strategy
+        )
+    }
+}
+
+impl Error for UnknownStrategyError {}
+
+/// What `FundManager::merge` did with each incoming fund
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct MergeReport {
+    /// Funds whose names were free
+    pub added: Vec<String>,
+    /// Funds left out because their names were taken
+    pub kept: Vec<String>,
+    /// Funds that replaced existing ones
+    pub replaced: Vec<String>,
+    /// Funds whose amounts were added to existing ones
+    pub summed: Vec<String>,
+    /// Funds added under a new name, as `(old name, new name)`
+    pub renamed: Vec<(String, String)>,
+}
+
+/// A wrapper around the errors of this crate and std::io::Error. Useful for binary crates dealing with
 /// `FundManager`s, as they may need to deal with any combination
 /// of these errors at once.
 ///
@@ -74,6 +232,9 @@ impl Error for DuplicateFundError {
 pub enum FundManagerError {
     FundNotFound(FundNotFoundError),
     DuplicateFund(DuplicateFundError),
+    NoRate(NoRateError),
+    Merge(MergeError),
+    Import(ImportError),
     Io(std::io::Error),
 }
 
@@ -82,17 +243,23 @@ impl fmt::Display for FundManagerError {
         match *self {
             FundManagerError::FundNotFound(ref e) => e.fmt(f),
             FundManagerError::DuplicateFund(ref e) => e.fmt(f),
+            FundManagerError::NoRate(ref e) => e.fmt(f),
+            FundManagerError::Merge(ref e) => e.fmt(f),
+            FundManagerError::Import(ref e) => e.fmt(f),
             FundManagerError::Io(ref e) => e.fmt(f),
         }
     }
 }
 
 impl Error for FundManagerError {
-    fn description(&self) -> &str {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
         match *self {
-            FundManagerError::FundNotFound(ref e) => e.description(),
-            FundManagerError::DuplicateFund(ref e) => e.description(),
-            FundManagerError::Io(ref e) => e.description(),
+            FundManagerError::FundNotFound(ref e) => Some(e),
+            FundManagerError::DuplicateFund(ref e) => Some(e),
+            FundManagerError::NoRate(ref e) => Some(e),
+            FundManagerError::Merge(ref e) => Some(e),
+            FundManagerError::Import(ref e) => Some(e),
+            FundManagerError::Io(ref e) => Some(e),
         }
     }
 }
@@ -109,26 +276,46 @@ impl From<DuplicateFundError> for FundManagerError {
     }
 }
 
+impl From<NoRateError> for FundManagerError {
+    fn from(err: NoRateError) -> FundManagerError {
+        FundManagerError::NoRate(err)
+    }
+}
+
+impl From<MergeError> for FundManagerError {
+    fn from(err: MergeError) -> FundManagerError {
+        FundManagerError::Merge(err)
+    }
+}
+
+impl From<ImportError> for FundManagerError {
+    fn from(err: ImportError) -> FundManagerError {
+        FundManagerError::Import(err)
+    }
+}
+
 impl From<std::io::Error> for FundManagerError {
     fn from(err: std::io::Error) -> FundManagerError {
         FundManagerError::Io(err)
     }
 }
 
-/// Manages storage and retrieval of Funds
+/// Manages storage and retrieval of Funds. Funds are kept ordered
+/// by name, so iterating over a `FundManager` is deterministic.
 #[derive(Debug, Default, PartialEq, Eq, Clone)]
 pub struct FundManager {
-    funds: HashMap<String, Fund>,
+    funds: BTreeMap<String, Fund>,
 }
 
 impl FundManager {
     pub fn new() -> FundManager {
         FundManager {
-            funds: HashMap::new(),
+            funds: BTreeMap::new(),
         }
     }
     /// Returns a new FundManager based on the contents of the
-    /// specified file
+    /// specified file. Each line of the file holds a fund's name, amount,
+    /// goal and, unless it is in US dollars, its currency, separated by colons.
     ///
     /// # Arguments
     ///
@@ -145,7 +332,8 @@ impl FundManager {
             .read(true)
             .write(true)
             .create(true)
-            .open(&fundfile)?;
+            .truncate(false)
+            .open(fundfile)?;
         let mut funds: Vec<(String, Fund)> = Vec::new();
         let buf_reader = BufReader::new(file);
 
@@ -153,41 +341,57 @@ impl FundManager {
             let line = line?;
             let fund_info: Vec<&str> = line.split_terminator(':').collect();
             if fund_info.len() < 3 {
-                return Err(From::from(std::io::Error::new(
+                return Err(std::io::Error::new(
                     std::io::ErrorKind::InvalidData,
                     format!("{:?} is invalid", fundfile),
-                )));
+                ));
             }
             let name = match fund_info[0].parse() {
                 Ok(name) => name,
                 Err(e) => {
-                    return Err(From::from(std::io::Error::new(
+                    return Err(std::io::Error::new(
                         std::io::ErrorKind::InvalidData,
                         format!("while parsing {:?}: {}", fundfile, e),
-                    )))
+                    ))
                 }
             };
             let amount: i32 = match fund_info[1].parse() {
                 Ok(amount) => amount,
                 Err(e) => {
-                    return Err(From::from(std::io::Error::new(
+                    return Err(std::io::Error::new(
                         std::io::ErrorKind::InvalidData,
                         format!("while parsing {:?}: {}", fundfile, e),
-                    )))
+                    ))
                 }
             };
             let goal: i32 = match fund_info[2].parse() {
                 Ok(goal) => goal,
                 Err(e) => {
-                    return Err(From::from(std::io::Error::new(
+                    return Err(std::io::Error::new(
                         std::io::ErrorKind::InvalidData,
                         format!("while parsing {:?}: {}", fundfile, e),
-                    )))
+                    ))
                 }
             };
+            let currency: Currency = match fund_info.get(3) {
+                Some(code) => match code.parse() {
+                    Ok(currency) => currency,
+                    Err(e) => {
+                        return Err(std::io::Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            format!("while parsing {:?}: {}", fundfile, e),
+                        ))
+                    }
+                },
+                None => Currency::default(),
+            };
             funds.push((
                 name,
-                Fund::new().with_amount(amount).with_goal(goal).build(),
+                Fund::new()
+                    .with_amount(amount)
+                    .with_goal(goal)
+                    .with_currency(currency)
+                    .build(),
             ));
         }
 
@@ -203,15 +407,23 @@ impl FundManager {
     /// # Errors
     ///
     /// * When the specified directory and/or parent directories
-    /// could not be created
+    ///   could not be created
     /// * When the 'fund' file could not be created or opened
     /// * When the 'fund' file could not be written to
     pub fn save(&self, fundfile: &Path) -> Result<(), std::io::Error> {
         fs::create_dir_all(fundfile.parent().unwrap_or(fundfile))?;
-        let file = OpenOptions::new().write(true).create(true).open(fundfile)?;
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(fundfile)?;
         let mut buf_writer = BufWriter::new(file);
         for fund in self {
-            let string = format!("{}:{}:{}\n", fund.0, fund.1.amount, fund.1.goal);
+            let mut string = format!("{}:{}:{}", fund.0, fund.1.amount, fund.1.goal);
+            if fund.1.currency != Currency::default() {
+                string.push_str(&format!(":{}", fund.1.currency));
+            }
+            string.push('\n');
             buf_writer.write_all(string.as_bytes())?;
         }
         Ok(())
@@ -227,10 +439,7 @@ impl FundManager {
     /// # Errors
     ///
     /// * When the fund cannot be found
-    #[deprecated(
-        since = "0.8.0",
-        note = "please use `fund` or `fund_mut` instead"
-    )]
+    #[deprecated(since = "0.8.0", note = "please use `fund` or `fund_mut` instead")]
     pub fn get_fund_by_name(&mut self, name: &str) -> Result<&mut Fund, &'static str> {
         match self.funds.get_mut(name) {
             Some(fund) => Ok(fund),
@@ -262,9 +471,7 @@ impl FundManager {
     pub fn fund(&self, name: &str) -> Result<&Fund, FundNotFoundError> {
         match self.funds.get(name) {
             Some(fund) => Ok(fund),
-            None => Err(FundNotFoundError {
-                name: String::from(name),
-            }),
+            None => Err(self.not_found(name)),
         }
     }
 
@@ -292,14 +499,91 @@ impl FundManager {
     /// assert_eq!(fund.amount, 200);
     /// ```
     pub fn fund_mut(&mut self, name: &str) -> Result<&mut Fund, FundNotFoundError> {
-        match self.funds.get_mut(name) {
-            Some(fund) => Ok(fund),
-            None => Err(FundNotFoundError {
+        // Matching on get_mut would keep self.funds borrowed in the None
+        // arm, where not_found needs it, so look the name up first
+        if !self.funds.contains_key(name) {
+            return Err(self.not_found(name));
+        }
+        Ok(self.funds.get_mut(name).unwrap())
+    }
+
+    /// Returns the name of the fund `name` refers to under `matching`. With
+    /// `NameMatching::Loose`, an exact match wins, then a match ignoring
+    /// case, then the one fund whose name starts with `name`.
+    ///
+    /// # Errors
+    ///
+    /// * When no fund matches, or `name` is the start of several funds'
+    ///   names, which are then suggested
+    ///
+    /// # Example
+    /// ```
+    /// use libfund::{Fund, FundManager, NameMatching};
+    ///
+    /// let mut funds = FundManager::new();
+    /// funds.add_fund("grocery", Fund::new().build()).unwrap();
+    /// funds.add_fund("gifts", Fund::new().build()).unwrap();
+    /// assert_eq!(funds.resolve("gro", NameMatching::Loose).unwrap(), "grocery");
+    /// assert_eq!(funds.resolve("Gifts", NameMatching::Loose).unwrap(), "gifts");
+    /// assert!(funds.resolve("g", NameMatching::Loose).is_err());
+    /// assert!(funds.resolve("gro", NameMatching::Exact).is_err());
+    /// ```
+    pub fn resolve(&self, name: &str, matching: NameMatching) -> Result<&str, FundNotFoundError> {
+        if let Some((found, _)) = self.funds.get_key_value(name) {
+            return Ok(found);
+        }
+        if matching == NameMatching::Exact {
+            return Err(self.not_found(name));
+        }
+        let lower = name.to_lowercase();
+        let same_case: Vec<&String> = self
+            .funds
+            .keys()
+            .filter(|found| found.to_lowercase() == lower)
+            .collect();
+        if let [found] = same_case[..] {
+            return Ok(found);
+        }
+        let prefixed: Vec<&String> = self
+            .funds
+            .keys()
+            .filter(|found| found.to_lowercase().starts_with(&lower))
+            .collect();
+        match prefixed[..] {
+            [found] => Ok(found),
+            [] => Err(self.not_found(name)),
+            _ => Err(FundNotFoundError {
                 name: String::from(name),
+                suggestions: prefixed.into_iter().cloned().collect(),
+                ambiguous: true,
             }),
         }
     }
 
+    /// Returns the error for a missing fund called `name`, suggesting the
+    /// funds whose names are fewest edits away, ignoring case, if that is
+    /// at most a third of its length
+    fn not_found(&self, name: &str) -> FundNotFoundError {
+        let lower = name.to_lowercase();
+        let limit = cmp::max(1, lower.chars().count().div_ceil(3));
+        let distances: Vec<(usize, &String)> = self
+            .funds
+            .keys()
+            .map(|found| (edit_distance(&lower, &found.to_lowercase()), found))
+            .filter(|&(distance, _)| distance <= limit)
+            .collect();
+        let closest = distances.iter().map(|&(distance, _)| distance).min();
+        FundNotFoundError {
+            name: String::from(name),
+            suggestions: distances
+                .into_iter()
+                .filter(|&(distance, _)| Some(distance) == closest)
+                .map(|(_, found)| found.clone())
+                .collect(),
+            ambiguous: false,
+        }
+    }
+
     #[deprecated(
         since = "0.8.0",
         note = "Slated for removal in 1.0.0, please use the getter functions to get the values 
@@ -346,7 +630,7 @@ impl FundManager {
     ///
     /// * `name` - the name of the new fund
     /// * `amount` - either `Some(x)`, where x is the starting amount of the new fund
-    ///  or `None` in which case the starting amount is 0
+    ///   or `None` in which case the starting amount is 0
     /// * `goal` - either `Some(x)`, where x is the goal for this fund, or `None` in which case the goal is 0
     ///
     /// # Errors
@@ -378,14 +662,255 @@ impl FundManager {
     pub fn rename(&mut self, old_name: &str, new_name: &str) -> Result<(), FundManagerError> {
         match self.funds.remove(old_name) {
             Some(fund) => self.add_fund(new_name, fund)?,
-            None => {
-                return Err(From::from(FundNotFoundError {
-                    name: String::from(old_name),
-                }))
-            }
+            None => return Err(From::from(self.not_found(old_name))),
         };
         Ok(())
     }
+
+    /// Adds the funds of `other`, settling funds whose names are taken as
+    /// `strategy` says, and returns what happened to each of them
+    ///
+    /// # Errors
+    ///
+    /// * When a name is taken and `strategy` is `MergeStrategy::Error`
+    /// * When summing funds in different currencies
+    ///
+    /// Nothing is merged when an error is returned.
+    ///
+    /// # Example
+    /// ```
+    /// use libfund::{Fund, FundManager, MergeStrategy};
+    ///
+    /// let mut funds = FundManager::new();
+    /// funds.add_fund("grocery", Fund::new().with_amount(100).build()).unwrap();
+    /// let mut other = FundManager::new();
+    /// other.add_fund("grocery", Fund::new().with_amount(50).build()).unwrap();
+    ///
+    /// let report = funds.merge(other.clone(), MergeStrategy::SumBalances).unwrap();
+    /// assert_eq!(report.summed, vec!["grocery"]);
+    /// assert_eq!(funds.fund("grocery").unwrap().amount, 150);
+    /// assert!(funds.merge(other, MergeStrategy::Error).is_err());
+    /// ```
+    pub fn merge(
+        &mut self,
+        other: FundManager,
+        strategy: MergeStrategy,
+    ) -> Result<MergeReport, MergeError> {
+        for (name, fund) in &other.funds {
+            if let Some(existing) = self.funds.get(name) {
+                let reason = match strategy {
+                    MergeStrategy::Error => "a fund with that name already exists",
+                    MergeStrategy::SumBalances if existing.currency != fund.currency => {
+                        "the funds are in different currencies"
+                    }
+                    _ => continue,
+                };
+                return Err(MergeError {
+                    name: name.clone(),
+                    reason,
+                });
+            }
+        }
+
+        let mut report = MergeReport::default();
+        for (name, fund) in &other.funds {
+            if !self.funds.contains_key(name) {
+                self.funds.insert(name.clone(), *fund);
+                report.added.push(name.clone());
+                continue;
+            }
+            match strategy {
+                MergeStrategy::KeepExisting | MergeStrategy::Error => {
+                    report.kept.push(name.clone())
+                }
+                MergeStrategy::TakeIncoming => {
+                    self.funds.insert(name.clone(), *fund);
+                    report.replaced.push(name.clone());
+                }
+                MergeStrategy::SumBalances => {
+                    if let Some(existing) = self.funds.get_mut(name) {
+                        existing.amount = existing.amount.saturating_add(fund.amount);
+                    }
+                    report.summed.push(name.clone());
+                }
+                MergeStrategy::RenameIncoming => {
+                    let new_name = (2..)
+                        .map(|n| format!("{}-{}", name, n))
+                        .find(|new_name| {
+                            !self.funds.contains_key(new_name)
+                                && !other.funds.contains_key(new_name)
+                        })
+                        .unwrap_or_default();
+                    self.funds.insert(new_name.clone(), *fund);
+                    report.renamed.push((name.clone(), new_name));
+                }
+            }
+        }
+        Ok(report)
+    }
+}
+
+impl FundManager {
+    /// Returns a `Fund` in `currency` holding the combined amounts and
+    /// goals of every fund, converted using `rates`
+    ///
+    /// # Errors
+    ///
+    /// * When a fund's currency has no rate to `currency`
+    ///
+    /// # Example
+    /// ```
+    /// use libfund::currency::{Currency, Rates};
+    /// use libfund::{Fund, FundManager};
+    ///
+    /// let eur = "EUR".parse().unwrap();
+    /// let mut rates = Rates::new();
+    /// rates.set(eur, Currency::USD, 1.5);
+    /// let mut funds = FundManager::new();
+    /// funds.add_fund("trip", Fund::new().with_amount(1000).with_currency(eur).build());
+    /// funds.add_fund("car", Fund::new().with_amount(500).with_goal(1000).build());
+    /// let total = funds.total(Currency::USD, &rates).unwrap();
+    /// assert_eq!(total.amount, 2000);
+    /// assert_eq!(total.goal, 1000);
+    /// ```
+    pub fn total(&self, currency: Currency, rates: &Rates) -> Result<Fund, NoRateError> {
+        let mut amount: i64 = 0;
+        let mut goal: i64 = 0;
+        for (_, fund) in self {
+            amount += rates.convert(i64::from(fund.amount), fund.currency, currency)?;
+            goal += rates.convert(i64::from(fund.goal), fund.currency, currency)?;
+        }
+        Ok(Fund::new()
+            .with_amount(clamp_i32(amount))
+            .with_goal(clamp_i32(goal))
+            .with_currency(currency)
+            .build())
+    }
+
+    /// Moves `amount` from the fund called `from` to the one called `to`,
+    /// converting it with `rates` when their currencies differ, and returns
+    /// the transaction to record in the history
+    ///
+    /// # Errors
+    ///
+    /// * When either fund does not exist
+    /// * When the funds' currencies have no rate between them
+    /// * When the converted amount does not fit in a fund
+    ///
+    /// Nothing is moved when an error is returned.
+    ///
+    /// # Example
+    /// ```
+    /// extern crate chrono;
+    /// extern crate libfund;
+    ///
+    /// use chrono::NaiveDate;
+    /// use libfund::currency::Rates;
+    /// use libfund::{Fund, FundManager};
+    ///
+    /// # fn main() {
+    /// let mut funds = FundManager::new();
+    /// funds.add_fund("grocery", Fund::new().with_amount(500).build()).unwrap();
+    /// funds.add_fund("savings", Fund::new().build()).unwrap();
+    /// let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+    /// let transaction = funds.transfer("grocery", "savings", 200, &Rates::new(), today).unwrap();
+    /// assert_eq!(funds.fund("savings").unwrap().amount, 200);
+    /// assert_eq!(transaction.legs.len(), 2);
+    /// # }
+    /// ```
+    pub fn transfer(
+        &mut self,
+        from: &str,
+        to: &str,
+        amount: i32,
+        rates: &Rates,
+        date: NaiveDate,
+    ) -> Result<Transaction, FundManagerError> {
+        let from_currency = self.fund(from)?.currency;
+        let to_currency = self.fund(to)?.currency;
+        let mut transaction = Transaction::new(date);
+        transaction.with_leg(from, -amount);
+        let received = if from_currency == to_currency {
+            amount
+        } else {
+            let rate = rates.rate(from_currency, to_currency).ok_or(NoRateError {
+                from: from_currency,
+                to: to_currency,
+            })?;
+            transaction.with_rate(rate);
+            let converted = rates.convert(i64::from(amount), from_currency, to_currency)?;
+            i32::try_from(converted).map_err(|_| {
+                io::Error::new(io::ErrorKind::InvalidInput, "converted amount is too large")
+            })?
+        };
+        transaction.with_leg(to, received);
+        self.fund_mut(from)?.spend(amount);
+        self.fund_mut(to)?.deposit(received);
+        Ok(transaction.build())
+    }
+
+    /// Spends from several funds at once, such as a single receipt covering
+    /// groceries and household goods, and returns the transaction to record
+    /// in the history, which has one leg for each of `parts`
+    ///
+    /// # Errors
+    ///
+    /// * When one of the funds does not exist, in which case nothing is
+    ///   spent from any of them
+    ///
+    /// # Example
+    /// ```
+    /// extern crate chrono;
+    /// extern crate libfund;
+    ///
+    /// use chrono::NaiveDate;
+    /// use libfund::{Fund, FundManager};
+    ///
+    /// # fn main() {
+    /// let mut funds = FundManager::new();
+    /// funds.add_fund("grocery", Fund::new().with_amount(10000).build()).unwrap();
+    /// funds.add_fund("household", Fund::new().with_amount(5000).build()).unwrap();
+    /// let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+    /// let receipt = funds.spend_split(&[("grocery", 8210), ("household", 4000)], today).unwrap();
+    /// assert_eq!(receipt.legs.len(), 2);
+    /// assert_eq!(funds.fund("household").unwrap().amount, 1000);
+    /// # }
+    /// ```
+    pub fn spend_split(
+        &mut self,
+        parts: &[(&str, i32)],
+        date: NaiveDate,
+    ) -> Result<Transaction, FundNotFoundError> {
+        for &(name, _) in parts {
+            self.fund(name)?;
+        }
+        let mut transaction = Transaction::new(date);
+        for &(name, amount) in parts {
+            self.fund_mut(name)?.spend(amount);
+            transaction.with_leg(name, -amount);
+        }
+        Ok(transaction.build())
+    }
+}
+
+/// Returns the number of characters that must be inserted, removed or
+/// replaced to turn `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, &b) in b.iter().enumerate() {
+            let replace = previous[j] + if a == b { 0 } else { 1 };
+            current.push(cmp::min(replace, cmp::min(previous[j + 1], current[j]) + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+fn clamp_i32(amount: i64) -> i32 {
+    amount.clamp(i64::from(i32::MIN), i64::from(i32::MAX)) as i32
 }
 
 impl<'a> IntoIterator for &'a FundManager {
@@ -406,39 +931,33 @@ impl<'a> IntoIterator for &'a mut FundManager {
     }
 }
 
+/// Adds funds from an iterator. As with the standard maps, a fund
+/// replaces any existing fund with the same name, like
+/// `MergeStrategy::TakeIncoming`. Use `FundManager::merge` to settle
+/// conflicts differently or to find out about them.
 impl Extend<(String, Fund)> for FundManager {
     fn extend<I: IntoIterator<Item = (String, Fund)>>(&mut self, iter: I) {
-        //! Extends a collection with the contents of an iterator. 
-        //! 
-        //! Warning!: Does not add funds that have the same name as previously existing funds.
-        for fund in iter {
-            if !self.funds.contains_key(&fund.0) {
-                self.add_fund(&fund.0, fund.1).unwrap();
-            }
+        for (name, fund) in iter {
+            self.funds.insert(name, fund);
         }
     }
 }
 
+/// Adds funds from an iterator, replacing any existing fund with the same
+/// name like the `Extend<(String, Fund)>` impl
 impl<'a> Extend<(&'a String, &'a Fund)> for FundManager {
     fn extend<I: IntoIterator<Item = (&'a String, &'a Fund)>>(&mut self, iter: I) {
-        //! Extends a collection with the contents of an iterator.
-        //! 
-        //! Warning: Does not add funds that have the same name as previously existing funds.
-        for fund in iter {
-            if !self.funds.contains_key(fund.0) {
-                self.add_fund(&fund.0, *fund.1).unwrap();
-            }
-        }
+        self.extend(iter.into_iter().map(|(name, fund)| (name.clone(), *fund)));
     }
 }
 
+/// Collects funds from an iterator. A fund replaces any earlier fund with
+/// the same name, like the `Extend` impls.
 impl FromIterator<(String, Fund)> for FundManager {
     fn from_iter<I: IntoIterator<Item = (String, Fund)>>(iter: I) -> Self {
-        let mut funds = HashMap::new();
-        for fund in iter {
-            funds.insert(fund.0, fund.1);
-        }
-        FundManager { funds }
+        let mut funds = FundManager::new();
+        funds.extend(iter);
+        funds
     }
 }
 
@@ -447,11 +966,12 @@ impl FromIterator<(String, Fund)> for FundManager {
 pub struct Fund {
     pub amount: i32,
     pub goal: i32,
+    pub currency: Currency,
 }
 
 impl PartialOrd for Fund {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.amount.cmp(&other.amount))
+        Some(self.cmp(other))
     }
 }
 
@@ -462,9 +982,14 @@ impl Ord for Fund {
 }
 
 impl Fund {
-    /// Returns a new Fund with default amounts of 0 for amount and goal
+    /// Returns a new Fund in US dollars with default amounts of 0 for
+    /// amount and goal
     pub fn new() -> Fund {
-        Fund { amount: 0, goal: 0 }
+        Fund {
+            amount: 0,
+            goal: 0,
+            currency: Currency::default(),
+        }
     }
 
     /// Sets `self`'s amount and returns a reference to itself.
@@ -497,6 +1022,21 @@ impl Fund {
         self
     }
 
+    /// Sets `self`'s currency and returns a reference to itself.
+    /// Intended for use as part of a builder pattern.
+    ///
+    /// # Example
+    /// ```
+    /// use libfund::Fund;
+    ///
+    /// let fund = Fund::new().with_currency("EUR".parse().unwrap()).build();
+    /// assert_eq!(fund.currency.code(), "EUR");
+    /// ```
+    pub fn with_currency(&mut self, currency: Currency) -> &mut Self {
+        self.currency = currency;
+        self
+    }
+
     /// Returns a new fund based on itself and consumes its reference.
     /// Intended as the last step of a builder pattern.
     ///
@@ -512,6 +1052,7 @@ impl Fund {
         Fund {
             amount: self.amount,
             goal: self.goal,
+            currency: self.currency,
         }
     }
 
@@ -532,33 +1073,96 @@ impl Fund {
     pub fn deposit(&mut self, amount: i32) {
         self.amount += amount;
     }
+
+    /// Returns how much money still needs to be added to reach the goal.
+    /// Negative when the fund is over its goal.
+    ///
+    /// # Example
+    /// ```
+    /// use libfund::Fund;
+    ///
+    /// let fund = Fund::new().with_amount(100).with_goal(500).build();
+    /// assert_eq!(fund.remaining(), 400);
+    ///
+    /// let overdrawn = Fund::new().with_amount(i32::MIN).with_goal(i32::MAX).build();
+    /// assert_eq!(overdrawn.remaining(), i64::from(i32::MAX) - i64::from(i32::MIN));
+    /// ```
+    pub fn remaining(&self) -> i64 {
+        i64::from(self.goal) - i64::from(self.amount)
+    }
+
+    /// Returns the fraction of the goal that has been reached, where
+    /// `1.0` means the goal has been met. A fund without a goal counts as
+    /// having met it unless its amount is negative.
+    ///
+    /// # Example
+    /// ```
+    /// use libfund::Fund;
+    ///
+    /// let fund = Fund::new().with_amount(100).with_goal(400).build();
+    /// assert_eq!(fund.progress(), 0.25);
+    /// ```
+    pub fn progress(&self) -> f64 {
+        if self.goal <= 0 {
+            if self.amount >= self.goal {
+                1.0
+            } else {
+                0.0
+            }
+        } else {
+            f64::from(self.amount) / f64::from(self.goal)
+        }
+    }
+
+    /// Returns the one line description of the fund used by its `Display`
+    /// impl, with amounts written using `money`
+    ///
+    /// # Example
+    /// ```
+    /// use libfund::Fund;
+    /// use libfund::money::MoneyFormat;
+    ///
+    /// let fund = Fund::new().with_amount(700).with_goal(500).build();
+    /// assert_eq!(
+    ///     fund.summary(&MoneyFormat::new()),
+    ///     " $7.00   / $5.00    -- over goal by $2.00"
+    /// );
+    /// ```
+    pub fn summary(&self, money: &MoneyFormat) -> String {
+        let remaining = self.remaining();
+        let status = if remaining < 0 {
+            format!("over goal by {}", money.format(-remaining))
+        } else {
+            format!("{} away from goal", money.format(remaining))
+        };
+        format!(
+            "{:^8} / {:<8} -- {}",
+            money.format(i64::from(self.amount)),
+            money.format(i64::from(self.goal)),
+            status
+        )
+    }
 }
 
 impl fmt::Display for Fund {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "{:^8} / {:<8} -- {} away from goal",
-            display_dollars(self.amount),
-            display_dollars(self.goal), //use std::path::PathBuf;
-            display_dollars(self.goal - self.amount)
-        )
+        let money = self.currency.money_format(&MoneyFormat::default());
+        write!(f, "{}", self.summary(&money))
     }
 }
 
-fn display_dollars(amount: i32) -> String {
-    let mut amount = amount.to_string();
-    while amount.len() < 3 {
-        amount.insert(0, '0');
-    }
-    let (dollars, cents) = amount.split_at(amount.len() - 2);
-    format!("${}.{}", dollars, cents)
+/// Formats an amount of cents as a dollar string, e.g. `100` as `$1.00`
+/// and `-123456` as `-$1,234.56`. See `money::MoneyFormat` for other styles.
+pub fn display_dollars(amount: i32) -> String {
+    MoneyFormat::default().format(i64::from(amount))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{display_dollars, Fund, FundManager};
-    use std::collections::HashMap;
+    use super::{display_dollars, edit_distance, Fund, FundManager, MergeStrategy, NameMatching};
+    use chrono::NaiveDate;
+    use currency::{Currency, Rates};
+    use std::collections::BTreeMap;
     use std::env;
 
     #[test]
@@ -590,6 +1194,20 @@ mod tests {
         assert_eq!(display_dollars(amount), "$1.00");
     }
 
+    #[test]
+    fn negative_dollar_display() {
+        assert_eq!(display_dollars(-5), "-$0.05");
+        assert_eq!(display_dollars(-123456), "-$1,234.56");
+    }
+
+    #[test]
+    fn over_goal_display() {
+        let fund = Fund::new().with_amount(1500).with_goal(1000).build();
+        assert!(format!("{}", fund).ends_with("-- over goal by $5.00"));
+        let fund = Fund::new().with_amount(-500).with_goal(1000).build();
+        assert!(format!("{}", fund).ends_with("-- $15.00 away from goal"));
+    }
+
     #[test]
     fn display() {
         let fund = Fund::new().with_amount(500).with_goal(1000).build();
@@ -607,7 +1225,7 @@ mod tests {
     #[test]
     fn adding_funds() {
         let mut funds = FundManager {
-            funds: HashMap::new(),
+            funds: BTreeMap::new(),
         };
         let result = funds.add_fund("Test", Fund::new().with_amount(100).with_goal(500).build());
         assert!(result.is_ok());
@@ -618,7 +1236,7 @@ mod tests {
     #[test]
     fn getting_funds() {
         let mut funds = FundManager {
-            funds: HashMap::new(),
+            funds: BTreeMap::new(),
         };
         funds
             .add_fund("Test", Fund::new().with_amount(100).with_goal(500).build())
@@ -630,7 +1248,7 @@ mod tests {
     #[test]
     fn get_mutable() {
         let mut funds = FundManager {
-            funds: HashMap::new(),
+            funds: BTreeMap::new(),
         };
         funds
             .add_fund("Test", Fund::new().with_amount(100).with_goal(500).build())
@@ -652,6 +1270,239 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn iterates_in_name_order() {
+        let funds: FundManager = vec![
+            (String::from("grocery"), Fund::new()),
+            (String::from("car"), Fund::new()),
+            (String::from("rent"), Fund::new()),
+        ]
+        .into_iter()
+        .collect();
+        let names: Vec<&String> = funds.into_iter().map(|fund| fund.0).collect();
+        assert_eq!(names, vec!["car", "grocery", "rent"]);
+    }
+
+    #[test]
+    fn later_funds_replace_earlier_ones() {
+        let funds: FundManager = vec![
+            (String::from("car"), Fund::new().with_amount(1).build()),
+            (String::from("car"), Fund::new().with_amount(2).build()),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(funds.fund("car").unwrap().amount, 2);
+
+        let mut extended = funds.clone();
+        extended.extend(vec![(
+            String::from("car"),
+            Fund::new().with_amount(3).build(),
+        )]);
+        assert_eq!(extended.fund("car").unwrap().amount, 3);
+        extended.extend(&funds);
+        assert_eq!(extended, funds);
+    }
+
+    fn merge_sources() -> (FundManager, FundManager) {
+        let existing: FundManager = vec![
+            (
+                String::from("car"),
+                Fund::new().with_amount(100).with_goal(500).build(),
+            ),
+            (String::from("rent"), Fund::new().with_amount(700).build()),
+        ]
+        .into_iter()
+        .collect();
+        let incoming: FundManager = vec![
+            (
+                String::from("car"),
+                Fund::new().with_amount(50).with_goal(900).build(),
+            ),
+            (String::from("car-2"), Fund::new().with_amount(5).build()),
+            (String::from("trip"), Fund::new().with_amount(10).build()),
+        ]
+        .into_iter()
+        .collect();
+        (existing, incoming)
+    }
+
+    #[test]
+    fn merges_with_each_strategy() {
+        let (existing, incoming) = merge_sources();
+
+        let mut funds = existing.clone();
+        let report = funds
+            .merge(incoming.clone(), MergeStrategy::KeepExisting)
+            .unwrap();
+        assert_eq!(report.added, vec!["car-2", "trip"]);
+        assert_eq!(report.kept, vec!["car"]);
+        assert_eq!(funds.fund("car").unwrap().amount, 100);
+
+        let mut funds = existing.clone();
+        let report = funds
+            .merge(incoming.clone(), MergeStrategy::TakeIncoming)
+            .unwrap();
+        assert_eq!(report.replaced, vec!["car"]);
+        assert_eq!(funds.fund("car").unwrap().goal, 900);
+
+        let mut funds = existing.clone();
+        let report = funds
+            .merge(incoming.clone(), MergeStrategy::SumBalances)
+            .unwrap();
+        assert_eq!(report.summed, vec!["car"]);
+        assert_eq!(funds.fund("car").unwrap().amount, 150);
+        assert_eq!(funds.fund("car").unwrap().goal, 500);
+
+        let mut funds = existing.clone();
+        let report = funds
+            .merge(incoming.clone(), MergeStrategy::RenameIncoming)
+            .unwrap();
+        assert_eq!(
+            report.renamed,
+            vec![(String::from("car"), String::from("car-3"))]
+        );
+        assert_eq!(funds.fund("car-3").unwrap().amount, 50);
+        assert_eq!(funds.fund("car-2").unwrap().amount, 5);
+
+        let mut funds = existing.clone();
+        assert!(funds.merge(incoming, MergeStrategy::Error).is_err());
+        assert_eq!(funds, existing);
+    }
+
+    #[test]
+    fn refuses_to_sum_different_currencies() {
+        let (mut funds, _) = merge_sources();
+        let mut incoming = FundManager::new();
+        let eur = "EUR".parse().unwrap();
+        incoming.add_fund("trip", Fund::new().build()).unwrap();
+        incoming
+            .add_fund("car", Fund::new().with_currency(eur).build())
+            .unwrap();
+        let error = funds
+            .merge(incoming, MergeStrategy::SumBalances)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "can't merge fund 'car': the funds are in different currencies"
+        );
+        assert!(funds.fund("trip").is_err());
+    }
+
+    #[test]
+    fn transfers_between_currencies() {
+        let eur = "EUR".parse().unwrap();
+        let mut rates = Rates::new();
+        rates.set(Currency::USD, eur, 0.5);
+        let mut funds = FundManager::new();
+        funds
+            .add_fund("car", Fund::new().with_amount(1000).build())
+            .unwrap();
+        funds
+            .add_fund("trip", Fund::new().with_currency(eur).build())
+            .unwrap();
+        funds
+            .add_fund(
+                "yen",
+                Fund::new().with_currency("JPY".parse().unwrap()).build(),
+            )
+            .unwrap();
+        let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+
+        let transaction = funds.transfer("car", "trip", 400, &rates, today).unwrap();
+        assert_eq!(transaction.rate, Some(0.5));
+        assert_eq!(transaction.legs[1].amount, 200);
+        assert_eq!(funds.fund("car").unwrap().amount, 600);
+        assert_eq!(funds.fund("trip").unwrap().amount, 200);
+
+        assert!(funds.transfer("car", "yen", 100, &rates, today).is_err());
+        assert!(funds
+            .transfer("car", "nothing", 100, &rates, today)
+            .is_err());
+        assert_eq!(funds.fund("car").unwrap().amount, 600);
+    }
+
+    #[test]
+    fn splits_spending_only_between_existing_funds() {
+        let mut funds = FundManager::new();
+        funds
+            .add_fund("grocery", Fund::new().with_amount(10_000).build())
+            .unwrap();
+        funds
+            .add_fund("gifts", Fund::new().with_amount(3_000).build())
+            .unwrap();
+        let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+
+        assert!(funds
+            .spend_split(&[("grocery", 8_210), ("nothing", 100)], today)
+            .is_err());
+        assert_eq!(funds.fund("grocery").unwrap().amount, 10_000);
+
+        let receipt = funds
+            .spend_split(&[("grocery", 8_210), ("gifts", 2_550)], today)
+            .unwrap();
+        assert!(!receipt.is_transfer());
+        assert_eq!(receipt.legs[1].amount, -2_550);
+        assert_eq!(funds.fund("grocery").unwrap().amount, 1_790);
+        assert_eq!(funds.fund("gifts").unwrap().amount, 450);
+    }
+
+    #[test]
+    fn resolves_names_loosely() {
+        let mut funds = FundManager::new();
+        for name in &["grocery", "groceries", "Car", "car repairs", "gifts"] {
+            funds.add_fund(name, Fund::new().build()).unwrap();
+        }
+
+        assert_eq!(funds.resolve("Car", NameMatching::Exact).unwrap(), "Car");
+        assert!(funds.resolve("car", NameMatching::Exact).is_err());
+        assert_eq!(funds.resolve("car", NameMatching::Loose).unwrap(), "Car");
+        assert_eq!(
+            funds.resolve("car r", NameMatching::Loose).unwrap(),
+            "car repairs"
+        );
+        assert_eq!(funds.resolve("GIF", NameMatching::Loose).unwrap(), "gifts");
+
+        let ambiguous = funds.resolve("groc", NameMatching::Loose).unwrap_err();
+        assert!(ambiguous.is_ambiguous());
+        assert_eq!(ambiguous.suggestions(), ["groceries", "grocery"]);
+        assert_eq!(
+            ambiguous.to_string(),
+            "'groc' matches several funds: 'groceries' and 'grocery'"
+        );
+    }
+
+    #[test]
+    fn suggests_close_names() {
+        let mut funds = FundManager::new();
+        for name in &["grocery", "gifts", "rent"] {
+            funds.add_fund(name, Fund::new().build()).unwrap();
+        }
+
+        let typo = funds.fund("Grocey").unwrap_err();
+        assert_eq!(
+            typo.to_string(),
+            "fund 'Grocey' not found, did you mean 'grocery'?"
+        );
+        assert_eq!(funds.fund_mut("rnet").unwrap_err().suggestions(), ["rent"]);
+        assert!(funds.fund("vacation").unwrap_err().suggestions().is_empty());
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn progress_towards_goal() {
+        assert_eq!(
+            Fund::new()
+                .with_amount(250)
+                .with_goal(1000)
+                .build()
+                .progress(),
+            0.25
+        );
+        assert_eq!(Fund::new().with_amount(50).build().progress(), 1.0);
+        assert_eq!(Fund::new().with_amount(-50).build().progress(), 0.0);
+    }
+
     #[test]
     fn renames_fund() {
         let mut funds = FundManager::new();
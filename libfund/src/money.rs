@@ -0,0 +1,467 @@
+//! Formatting of money amounts, which libfund stores as a whole number
+//! of the currency's smallest unit (e.g. cents), and parsing of amounts
+//! typed by the user.
+
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use currency::Currency;
+
+/// How negative amounts are written
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum NegativeStyle {
+    /// `-$5.00`
+    Minus,
+    /// `($5.00)`, as is common in accounting
+    Parentheses,
+}
+
+/// Describes how to turn an amount of cents into a string. The default
+/// matches US conventions, e.g. `-$1,234.56`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MoneyFormat {
+    pub symbol: String,
+    /// Whether the symbol follows the number (`1.234,56 €`) instead of
+    /// preceding it (`$1,234.56`)
+    pub symbol_after: bool,
+    pub decimal_separator: char,
+    /// The character used to group thousands, or `None` to not group them
+    pub thousands_separator: Option<char>,
+    pub negative: NegativeStyle,
+    /// How many digits of the amount come after the decimal separator
+    pub decimals: u32,
+}
+
+impl Default for MoneyFormat {
+    fn default() -> MoneyFormat {
+        MoneyFormat::new()
+    }
+}
+
+impl MoneyFormat {
+    /// Returns the US style format: `$` before the number, `,` between
+    /// thousands, `.` before the two digits of cents and a leading `-` for
+    /// negative amounts
+    pub fn new() -> MoneyFormat {
+        MoneyFormat {
+            symbol: String::from("$"),
+            symbol_after: false,
+            decimal_separator: '.',
+            thousands_separator: Some(','),
+            negative: NegativeStyle::Minus,
+            decimals: 2,
+        }
+    }
+
+    /// Returns the separators and symbol placement customary for a POSIX
+    /// locale name such as `de_DE.UTF-8`, or `None` if the language is
+    /// not known. The symbol is left as `$`.
+    ///
+    /// # Example
+    /// ```
+    /// use libfund::money::MoneyFormat;
+    ///
+    /// let format = MoneyFormat::for_locale("de_DE.UTF-8").unwrap();
+    /// assert_eq!(format.format(-123456), "-1.234,56 $");
+    /// ```
+    pub fn for_locale(locale: &str) -> Option<MoneyFormat> {
+        let language = locale.split(['_', '.', '@', '-']).next().unwrap_or("");
+        let mut format = MoneyFormat::new();
+        match language {
+            "en" | "ja" | "zh" | "ko" | "he" | "th" | "hi" => {}
+            "de" | "es" | "it" | "nl" | "pt" | "da" | "id" | "tr" | "el" | "ro" => {
+                format
+                    .with_thousands_separator(Some('.'))
+                    .with_decimal_separator(',')
+                    .with_symbol_after(true);
+            }
+            "fr" | "ru" | "pl" | "cs" | "sk" | "sv" | "nb" | "fi" | "uk" | "hu" | "bg" => {
+                format
+                    .with_thousands_separator(Some(' '))
+                    .with_decimal_separator(',')
+                    .with_symbol_after(true);
+            }
+            _ => return None,
+        }
+        Some(format)
+    }
+
+    /// Sets the currency symbol. Intended for use as part of a builder pattern.
+    pub fn with_symbol(&mut self, symbol: &str) -> &mut Self {
+        self.symbol = String::from(symbol);
+        self
+    }
+
+    /// Sets whether the symbol follows the number. Intended for use as
+    /// part of a builder pattern.
+    pub fn with_symbol_after(&mut self, symbol_after: bool) -> &mut Self {
+        self.symbol_after = symbol_after;
+        self
+    }
+
+    /// Sets the decimal separator. Intended for use as part of a builder pattern.
+    pub fn with_decimal_separator(&mut self, separator: char) -> &mut Self {
+        self.decimal_separator = separator;
+        self
+    }
+
+    /// Sets the thousands separator. Intended for use as part of a builder pattern.
+    pub fn with_thousands_separator(&mut self, separator: Option<char>) -> &mut Self {
+        self.thousands_separator = separator;
+        self
+    }
+
+    /// Sets how negative amounts are written. Intended for use as part of
+    /// a builder pattern.
+    pub fn with_negative_style(&mut self, negative: NegativeStyle) -> &mut Self {
+        self.negative = negative;
+        self
+    }
+
+    /// Sets the number of decimal digits. Intended for use as part of a
+    /// builder pattern.
+    pub fn with_decimals(&mut self, decimals: u32) -> &mut Self {
+        self.decimals = decimals;
+        self
+    }
+
+    /// Returns a new format based on itself. Intended as the last step of
+    /// a builder pattern.
+    pub fn build(&self) -> MoneyFormat {
+        self.clone()
+    }
+
+    /// Formats `amount`, given in the smallest unit of the currency
+    ///
+    /// # Example
+    /// ```
+    /// use libfund::money::{MoneyFormat, NegativeStyle};
+    ///
+    /// let format = MoneyFormat::new();
+    /// assert_eq!(format.format(123456789), "$1,234,567.89");
+    /// assert_eq!(format.format(-5), "-$0.05");
+    ///
+    /// let format = MoneyFormat::new()
+    ///     .with_negative_style(NegativeStyle::Parentheses)
+    ///     .build();
+    /// assert_eq!(format.format(-500), "($5.00)");
+    /// ```
+    pub fn format(&self, amount: i64) -> String {
+        let magnitude = amount.unsigned_abs();
+        let divisor = 10u64.pow(self.decimals.min(19));
+        let whole = (magnitude / divisor).to_string();
+
+        let mut number = String::with_capacity(whole.len() * 2);
+        for (i, digit) in whole.chars().enumerate() {
+            if i > 0 && (whole.len() - i).is_multiple_of(3) {
+                if let Some(separator) = self.thousands_separator {
+                    number.push(separator);
+                }
+            }
+            number.push(digit);
+        }
+        if self.decimals > 0 {
+            number.push(self.decimal_separator);
+            number.push_str(&format!(
+                "{:0width$}",
+                magnitude % divisor,
+                width = self.decimals as usize
+            ));
+        }
+
+        let money = if self.symbol_after {
+            format!("{} {}", number, self.symbol)
+        } else {
+            format!("{}{}", self.symbol, number)
+        };
+        if amount >= 0 {
+            money
+        } else {
+            match self.negative {
+                NegativeStyle::Minus => format!("-{}", money),
+                NegativeStyle::Parentheses => format!("({})", money),
+            }
+        }
+    }
+}
+
+/// The error returned when an amount is malformed, or can't be expressed
+/// in the currency it was meant for
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct InvalidAmountError {
+    amount: String,
+    currency: Option<Currency>,
+}
+
+impl fmt::Display for InvalidAmountError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.currency {
+            None => write!(
+                f,
+                "'{}' is not an amount, expected a number such as 12.50",
+                self.amount
+            ),
+            Some(currency) if currency.minor_units() == 0 => write!(
+                f,
+                "'{}' is not an amount of {}, which has no decimals",
+                self.amount, currency
+            ),
+            Some(currency) => write!(
+                f,
+                "'{}' is not an amount of {}, which has up to {} decimals",
+                self.amount,
+                currency,
+                currency.minor_units()
+            ),
+        }
+    }
+}
+
+impl Error for InvalidAmountError {}
+
+/// An amount of money as written, such as `20`, `7.5` or `-12.50`, before
+/// it is known which currency it is in
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Amount {
+    text: String,
+    negative: bool,
+    digits: u64,
+    decimals: u32,
+}
+
+/// Parses digits with an optional leading `-` and decimal point. Thousands
+/// separators, currency symbols and other signs are rejected.
+impl FromStr for Amount {
+    type Err = InvalidAmountError;
+
+    fn from_str(s: &str) -> Result<Amount, InvalidAmountError> {
+        let invalid = || InvalidAmountError {
+            amount: String::from(s),
+            currency: None,
+        };
+        let text = s.trim();
+        let (negative, unsigned) = match text.strip_prefix('-') {
+            Some(unsigned) => (true, unsigned),
+            None => (false, text),
+        };
+        let (whole, fraction) = match unsigned.split_once('.') {
+            Some((whole, fraction)) if !fraction.is_empty() => (whole, fraction),
+            Some(_) => return Err(invalid()),
+            None => (unsigned, ""),
+        };
+        if whole.is_empty()
+            || !format!("{}{}", whole, fraction)
+                .bytes()
+                .all(|b| b.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+        Ok(Amount {
+            text: String::from(text),
+            negative,
+            digits: format!("{}{}", whole, fraction)
+                .parse()
+                .map_err(|_| invalid())?,
+            decimals: fraction.len() as u32,
+        })
+    }
+}
+
+impl Amount {
+    /// Returns the amount in the smallest unit of `currency`
+    ///
+    /// # Errors
+    ///
+    /// * When the amount has more decimals than `currency`, not counting
+    ///   trailing zeros, or is too large to store
+    ///
+    /// # Example
+    /// ```
+    /// use libfund::currency::Currency;
+    /// use libfund::money::Amount;
+    ///
+    /// let amount: Amount = "82.1".parse().unwrap();
+    /// assert_eq!(amount.to_minor_units(Currency::USD), Ok(8210));
+    /// assert!(amount.to_minor_units("JPY".parse().unwrap()).is_err());
+    /// ```
+    pub fn to_minor_units(&self, currency: Currency) -> Result<i32, InvalidAmountError> {
+        let invalid = || InvalidAmountError {
+            amount: self.text.clone(),
+            currency: Some(currency),
+        };
+        let (mut digits, mut decimals) = (self.digits, self.decimals);
+        while decimals > currency.minor_units() && digits % 10 == 0 {
+            digits /= 10;
+            decimals -= 1;
+        }
+        if decimals > currency.minor_units() {
+            return Err(invalid());
+        }
+        let scale = 10u64.pow(currency.minor_units() - decimals);
+        let amount = digits
+            .checked_mul(scale)
+            .and_then(|amount| i32::try_from(amount).ok())
+            .ok_or_else(invalid)?;
+        Ok(if self.negative { -amount } else { amount })
+    }
+}
+
+/// Parses an amount written as described for `Amount` into the smallest
+/// unit of `currency`
+///
+/// # Example
+/// ```
+/// use libfund::currency::Currency;
+/// use libfund::money::parse_amount;
+///
+/// let yen = "JPY".parse().unwrap();
+/// assert_eq!(parse_amount("7.5", Currency::USD), Ok(750));
+/// assert_eq!(parse_amount("20", Currency::USD), Ok(2000));
+/// assert_eq!(parse_amount("1000", yen), Ok(1000));
+/// assert!(parse_amount("1.234", Currency::USD).is_err());
+/// assert!(parse_amount("12.50", yen).is_err());
+/// ```
+pub fn parse_amount(amount: &str, currency: Currency) -> Result<i32, InvalidAmountError> {
+    amount.parse::<Amount>()?.to_minor_units(currency)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_amount, Amount, MoneyFormat, NegativeStyle};
+    use currency::Currency;
+    use proptest::prelude::*;
+
+    #[test]
+    fn parses_amounts() {
+        let usd = Currency::USD;
+        assert_eq!(parse_amount("50", usd), Ok(5000));
+        assert_eq!(parse_amount("82.1", usd), Ok(8210));
+        assert_eq!(parse_amount(" 0.05 ", usd), Ok(5));
+        assert_eq!(parse_amount("-12.50", usd), Ok(-1250));
+        assert_eq!(parse_amount("1.230", usd), Ok(123));
+        for invalid in &[
+            "",
+            "-",
+            "abc",
+            "1.",
+            ".50",
+            "1.2.3",
+            "1,000",
+            "+5",
+            "--5",
+            "$5",
+            "1.234",
+            "99999999999",
+        ] {
+            assert!(parse_amount(invalid, usd).is_err(), "{} parsed", invalid);
+        }
+    }
+
+    #[test]
+    fn parses_amounts_in_any_currency() {
+        let yen: Currency = "JPY".parse().unwrap();
+        let dinar: Currency = "KWD".parse().unwrap();
+        assert_eq!(parse_amount("1000", yen), Ok(1000));
+        assert_eq!(parse_amount("1000.00", yen), Ok(1000));
+        assert_eq!(parse_amount("1.5", dinar), Ok(1500));
+        assert_eq!(parse_amount("1.234", dinar), Ok(1234));
+        assert_eq!(
+            parse_amount("12.5", yen).unwrap_err().to_string(),
+            "'12.5' is not an amount of JPY, which has no decimals"
+        );
+        let amount: Amount = "3000000".parse().unwrap();
+        assert_eq!(amount.to_minor_units(yen), Ok(3_000_000));
+        assert!(amount.to_minor_units(dinar).is_err());
+    }
+
+    #[test]
+    fn formats_small_amounts() {
+        let format = MoneyFormat::new();
+        assert_eq!(format.format(0), "$0.00");
+        assert_eq!(format.format(5), "$0.05");
+        assert_eq!(format.format(-5), "-$0.05");
+        assert_eq!(format.format(-100), "-$1.00");
+    }
+
+    #[test]
+    fn groups_thousands() {
+        let format = MoneyFormat::new();
+        assert_eq!(format.format(99999), "$999.99");
+        assert_eq!(format.format(100000), "$1,000.00");
+        assert_eq!(format.format(-123456789), "-$1,234,567.89");
+        let format = MoneyFormat::new().with_thousands_separator(None).build();
+        assert_eq!(format.format(123456789), "$1234567.89");
+    }
+
+    #[test]
+    fn formats_parentheses() {
+        let format = MoneyFormat::new()
+            .with_negative_style(NegativeStyle::Parentheses)
+            .build();
+        assert_eq!(format.format(-123456), "($1,234.56)");
+        assert_eq!(format.format(123456), "$1,234.56");
+    }
+
+    #[test]
+    fn formats_locales() {
+        let format = MoneyFormat::for_locale("fr_FR.UTF-8").unwrap();
+        assert_eq!(format.format(123456), "1 234,56 $");
+        assert_eq!(
+            MoneyFormat::for_locale("en_US.UTF-8"),
+            Some(MoneyFormat::new())
+        );
+        assert_eq!(MoneyFormat::for_locale("C"), None);
+    }
+
+    #[test]
+    fn formats_extremes() {
+        let format = MoneyFormat::new();
+        assert_eq!(format.format(i64::MAX), "$92,233,720,368,547,758.07");
+        assert_eq!(format.format(i64::MIN), "-$92,233,720,368,547,758.08");
+        let format = MoneyFormat::new().with_decimals(0).build();
+        assert_eq!(format.format(-1500), "-$1,500");
+    }
+
+    proptest! {
+        #[test]
+        fn keeps_every_digit(amount in any::<i64>()) {
+            let formatted = MoneyFormat::new().format(amount);
+            let digits: String = formatted.chars().filter(|c| c.is_ascii_digit()).collect();
+            prop_assert_eq!(digits.parse::<u64>().unwrap(), amount.unsigned_abs());
+            prop_assert!(digits.len() >= 3);
+        }
+
+        #[test]
+        fn marks_negatives(amount in any::<i64>()) {
+            let minus = MoneyFormat::new().format(amount);
+            prop_assert_eq!(minus.starts_with("-$"), amount < 0);
+            prop_assert!(minus.starts_with('$') || minus.starts_with("-$"));
+
+            let parentheses = MoneyFormat::new()
+                .with_negative_style(NegativeStyle::Parentheses)
+                .build()
+                .format(amount);
+            prop_assert_eq!(parentheses.starts_with("($") && parentheses.ends_with(')'), amount < 0);
+            prop_assert!(!parentheses.contains('-'));
+        }
+
+        #[test]
+        fn groups_every_three_digits(amount in any::<i64>()) {
+            let formatted = MoneyFormat::new().format(amount);
+            let whole = formatted
+                .trim_start_matches('-')
+                .trim_start_matches('$')
+                .split('.')
+                .next()
+                .unwrap();
+            let groups: Vec<&str> = whole.split(',').collect();
+            prop_assert!(!groups[0].is_empty() && groups[0].len() <= 3);
+            for group in &groups[1..] {
+                prop_assert_eq!(group.len(), 3);
+            }
+            prop_assert_eq!(formatted.split('.').nth(1).unwrap().len(), 2);
+        }
+    }
+}
@@ -0,0 +1,298 @@
+//! Statistics about how money has moved in and out of funds, computed
+//! from their `History`.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Datelike, Duration, NaiveDate};
+
+use history::History;
+use {Fund, FundManager};
+
+/// How many of the largest expenses are kept in `FundStats`
+const LARGEST_EXPENSES: usize = 3;
+
+/// The error returned when parsing an unknown period
+#[derive(Debug)]
+pub struct UnknownPeriodError {
+    period: String,
+}
+
+impl fmt::Display for UnknownPeriodError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "unknown period '{}', expected month, quarter or year",
+            self.period
+        )
+    }
+}
+
+impl Error for UnknownPeriodError {}
+
+/// A calendar period statistics are gathered over
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Period {
+    Month,
+    Quarter,
+    Year,
+}
+
+impl Period {
+    /// Returns the first day of the period containing `date`
+    ///
+    /// # Example
+    /// ```
+    /// extern crate chrono;
+    /// extern crate libfund;
+    ///
+    /// use chrono::NaiveDate;
+    /// use libfund::stats::Period;
+    ///
+    /// # fn main() {
+    /// let date = NaiveDate::from_ymd_opt(2026, 8, 17).unwrap();
+    /// assert_eq!(Period::Quarter.start(date), NaiveDate::from_ymd_opt(2026, 7, 1).unwrap());
+    /// # }
+    /// ```
+    pub fn start(self, date: NaiveDate) -> NaiveDate {
+        let month = match self {
+            Period::Month => date.month(),
+            Period::Quarter => (date.month() - 1) / 3 * 3 + 1,
+            Period::Year => 1,
+        };
+        NaiveDate::from_ymd_opt(date.year(), month, 1).unwrap_or(date)
+    }
+
+    /// Returns the first day of the period before the one containing `date`
+    pub fn previous_start(self, date: NaiveDate) -> NaiveDate {
+        self.start(self.start(date) - Duration::days(1))
+    }
+}
+
+impl FromStr for Period {
+    type Err = UnknownPeriodError;
+
+    fn from_str(s: &str) -> Result<Period, UnknownPeriodError> {
+        match s {
+            "month" => Ok(Period::Month),
+            "quarter" => Ok(Period::Quarter),
+            "year" => Ok(Period::Year),
+            _ => Err(UnknownPeriodError {
+                period: String::from(s),
+            }),
+        }
+    }
+}
+
+/// Statistics for a single fund. Amounts are in the fund's currency and
+/// are always positive.
+#[derive(Debug, PartialEq, Clone)]
+pub struct FundStats {
+    pub name: String,
+    /// Money that entered the fund during the period, including transfers
+    pub money_in: i64,
+    /// Money that left the fund during the period, including transfers
+    pub money_out: i64,
+    /// Money spent from the fund during the period, not counting transfers
+    pub spent: i64,
+    /// Money spent from the fund during the previous period
+    pub previous_spent: i64,
+    /// Spending averaged over every month since the first transaction
+    pub average_monthly_spend: i64,
+    /// The biggest expenses of the period, largest first
+    pub largest_expenses: Vec<(NaiveDate, i64)>,
+    /// How many days the fund's balance lasts at the average spending
+    /// rate, or `None` if nothing has been spent
+    pub runway_days: Option<i64>,
+}
+
+impl FundStats {
+    /// Returns the change in spending from the previous period as a
+    /// fraction, e.g. `0.25` for 25% more, or `None` if nothing was spent
+    /// in the previous period
+    pub fn spending_change(&self) -> Option<f64> {
+        if self.previous_spent == 0 {
+            None
+        } else {
+            Some((self.spent - self.previous_spent) as f64 / self.previous_spent as f64)
+        }
+    }
+}
+
+/// Gathers statistics about the fund called `name` from `history`, for
+/// the `period` containing `today`
+pub fn fund_stats(
+    name: &str,
+    fund: &Fund,
+    history: &History,
+    period: Period,
+    today: NaiveDate,
+) -> FundStats {
+    let start = period.start(today);
+    let previous_start = period.previous_start(today);
+    let mut stats = FundStats {
+        name: String::from(name),
+        money_in: 0,
+        money_out: 0,
+        spent: 0,
+        previous_spent: 0,
+        average_monthly_spend: 0,
+        largest_expenses: Vec::new(),
+        runway_days: None,
+    };
+    let mut total_spent: i64 = 0;
+    let mut first_date: Option<NaiveDate> = None;
+
+    for transaction in history.iter().filter(|t| t.date <= today) {
+        for leg in transaction.legs.iter().filter(|leg| leg.fund == name) {
+            let amount = i64::from(leg.amount);
+            let is_expense = amount < 0 && !transaction.is_transfer();
+            if first_date.is_none() {
+                first_date = Some(transaction.date);
+            }
+            if is_expense {
+                total_spent -= amount;
+            }
+            if transaction.date >= start {
+                if amount > 0 {
+                    stats.money_in += amount;
+                } else {
+                    stats.money_out -= amount;
+                }
+                if is_expense {
+                    stats.spent -= amount;
+                    stats.largest_expenses.push((transaction.date, -amount));
+                }
+            } else if transaction.date >= previous_start && is_expense {
+                stats.previous_spent -= amount;
+            }
+        }
+    }
+
+    stats
+        .largest_expenses
+        .sort_by(|a, b| b.1.cmp(&a.1).then(b.0.cmp(&a.0)));
+    stats.largest_expenses.truncate(LARGEST_EXPENSES);
+
+    if let Some(first_date) = first_date {
+        let months = months_between(first_date, today) + 1;
+        stats.average_monthly_spend = total_spent / months;
+    }
+    if stats.average_monthly_spend > 0 {
+        let balance = i64::from(fund.amount).max(0);
+        stats.runway_days = Some(balance * 365 / (stats.average_monthly_spend * 12));
+    }
+    stats
+}
+
+/// Gathers statistics for every fund in `funds`, in name order
+pub fn all_stats(
+    funds: &FundManager,
+    history: &History,
+    period: Period,
+    today: NaiveDate,
+) -> Vec<FundStats> {
+    funds
+        .into_iter()
+        .map(|(name, fund)| fund_stats(name, fund, history, period, today))
+        .collect()
+}
+
+/// Returns the number of whole calendar months from `from` to `to`
+fn months_between(from: NaiveDate, to: NaiveDate) -> i64 {
+    let months = (to.year() - from.year()) * 12 + to.month() as i32 - from.month() as i32;
+    i64::from(months.max(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fund_stats, Period};
+    use chrono::NaiveDate;
+    use history::{History, Transaction};
+    use Fund;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    fn history() -> History {
+        let mut history = History::new();
+        history.push(
+            Transaction::new(date(8, 2))
+                .with_leg("grocery", -6000)
+                .build(),
+        );
+        history.push(
+            Transaction::new(date(9, 5))
+                .with_leg("grocery", -4000)
+                .build(),
+        );
+        history.push(
+            Transaction::new(date(10, 1))
+                .with_leg("grocery", 10000)
+                .build(),
+        );
+        history.push(
+            Transaction::new(date(10, 3))
+                .with_leg("grocery", -3000)
+                .build(),
+        );
+        history.push(
+            Transaction::new(date(10, 9))
+                .with_leg("grocery", -2000)
+                .build(),
+        );
+        history.push(
+            Transaction::new(date(10, 10))
+                .with_leg("grocery", -1000)
+                .with_leg("car", 1000)
+                .build(),
+        );
+        history.push(Transaction::new(date(10, 12)).with_leg("car", -500).build());
+        history
+    }
+
+    #[test]
+    fn finds_period_starts() {
+        assert_eq!(Period::Month.start(date(10, 18)), date(10, 1));
+        assert_eq!(Period::Quarter.start(date(12, 31)), date(10, 1));
+        assert_eq!(Period::Year.start(date(10, 18)), date(1, 1));
+        assert_eq!(
+            Period::Month.previous_start(date(1, 15)),
+            NaiveDate::from_ymd_opt(2025, 12, 1).unwrap()
+        );
+        assert_eq!(
+            Period::Quarter.previous_start(date(2, 1)),
+            NaiveDate::from_ymd_opt(2025, 10, 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn gathers_monthly_stats() {
+        let fund = Fund::new().with_amount(7500).build();
+        let stats = fund_stats("grocery", &fund, &history(), Period::Month, date(10, 18));
+        assert_eq!(stats.money_in, 10000);
+        assert_eq!(stats.money_out, 6000);
+        assert_eq!(stats.spent, 5000);
+        assert_eq!(stats.previous_spent, 4000);
+        assert_eq!(stats.spending_change(), Some(0.25));
+        assert_eq!(
+            stats.largest_expenses,
+            vec![(date(10, 3), 3000), (date(10, 9), 2000)]
+        );
+        assert_eq!(stats.average_monthly_spend, 5000);
+        assert_eq!(stats.runway_days, Some(45));
+    }
+
+    #[test]
+    fn gathers_yearly_stats() {
+        let fund = Fund::new().with_amount(500).build();
+        let stats = fund_stats("car", &fund, &history(), Period::Year, date(10, 18));
+        assert_eq!(stats.money_in, 1000);
+        assert_eq!(stats.money_out, 500);
+        assert_eq!(stats.spent, 500);
+        assert_eq!(stats.previous_spent, 0);
+        assert_eq!(stats.spending_change(), None);
+    }
+}
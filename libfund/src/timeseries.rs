@@ -0,0 +1,197 @@
+//! Series of values over time built from a fund's `History`, for use
+//! in charts and forecasts.
+
+use chrono::{Datelike, Duration, NaiveDate};
+
+use history::History;
+use Fund;
+
+/// Returns the balance of the fund called `name` at the end of every day
+/// from `from` to `to`, inclusive. Balances are worked out backwards from
+/// the fund's current amount, so transactions dated after `to` are taken
+/// into account.
+///
+/// # Example
+/// ```
+/// extern crate chrono;
+/// extern crate libfund;
+///
+/// use chrono::NaiveDate;
+/// use libfund::history::{History, Transaction};
+/// use libfund::timeseries::daily_balances;
+/// use libfund::Fund;
+///
+/// # fn main() {
+/// let day = |d| NaiveDate::from_ymd_opt(2026, 10, d).unwrap();
+/// let mut history = History::new();
+/// history.push(Transaction::new(day(2)).with_leg("grocery", -500).build());
+/// let fund = Fund::new().with_amount(1500).build();
+/// let balances = daily_balances("grocery", &fund, &history, day(1), day(3));
+/// assert_eq!(balances, vec![(day(1), 2000), (day(2), 1500), (day(3), 1500)]);
+/// # }
+/// ```
+pub fn daily_balances(
+    name: &str,
+    fund: &Fund,
+    history: &History,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Vec<(NaiveDate, i64)> {
+    let mut balance = i64::from(fund.amount);
+    let mut changes: Vec<(NaiveDate, i64)> = Vec::new();
+    for transaction in history.iter() {
+        for leg in transaction.legs.iter().filter(|leg| leg.fund == name) {
+            if transaction.date > to {
+                balance -= i64::from(leg.amount);
+            } else if transaction.date > from {
+                changes.push((transaction.date, i64::from(leg.amount)));
+            }
+        }
+    }
+
+    let mut balances = Vec::new();
+    let mut date = to;
+    while date >= from {
+        balances.push((date, balance));
+        while let Some(&(change_date, amount)) = changes.last() {
+            if change_date < date {
+                break;
+            }
+            balance -= amount;
+            changes.pop();
+        }
+        date -= Duration::days(1);
+    }
+    balances.reverse();
+    balances
+}
+
+/// Returns how much was spent from the fund called `name` in each of the
+/// `months` calendar months up to and including the one containing
+/// `today`, oldest first. Each month is identified by its first day.
+/// Transfers between funds do not count as spending.
+pub fn monthly_spending(
+    name: &str,
+    history: &History,
+    months: u32,
+    today: NaiveDate,
+) -> Vec<(NaiveDate, i64)> {
+    let mut spending: Vec<(NaiveDate, i64)> = Vec::new();
+    let mut month = first_of_month(today);
+    for _ in 0..months {
+        spending.push((month, 0));
+        month = first_of_month(month - Duration::days(1));
+    }
+    spending.reverse();
+
+    for transaction in history
+        .iter()
+        .filter(|t| !t.is_transfer() && t.date <= today)
+    {
+        let month = first_of_month(transaction.date);
+        if let Some(entry) = spending.iter_mut().find(|entry| entry.0 == month) {
+            for leg in transaction
+                .legs
+                .iter()
+                .filter(|leg| leg.fund == name && leg.amount < 0)
+            {
+                entry.1 -= i64::from(leg.amount);
+            }
+        }
+    }
+    spending
+}
+
+/// Returns how much was spent from each fund between `from` and `to`,
+/// inclusive, in the order funds first appear in `history`. Transfers
+/// between funds do not count as spending.
+pub fn spending_by_fund(history: &History, from: NaiveDate, to: NaiveDate) -> Vec<(String, i64)> {
+    let mut spending: Vec<(String, i64)> = Vec::new();
+    for transaction in history.iter() {
+        if transaction.is_transfer() || transaction.date < from || transaction.date > to {
+            continue;
+        }
+        for leg in transaction.legs.iter().filter(|leg| leg.amount < 0) {
+            match spending.iter_mut().find(|entry| entry.0 == leg.fund) {
+                Some(entry) => entry.1 -= i64::from(leg.amount),
+                None => spending.push((leg.fund.clone(), -i64::from(leg.amount))),
+            }
+        }
+    }
+    spending
+}
+
+fn first_of_month(date: NaiveDate) -> NaiveDate {
+    NaiveDate::from_ymd_opt(date.year(), date.month(), 1).unwrap_or(date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{daily_balances, monthly_spending, spending_by_fund};
+    use chrono::NaiveDate;
+    use history::{History, Transaction};
+    use Fund;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    fn history() -> History {
+        let mut history = History::new();
+        history.push(
+            Transaction::new(date(9, 20))
+                .with_leg("grocery", -1000)
+                .build(),
+        );
+        history.push(
+            Transaction::new(date(10, 2))
+                .with_leg("grocery", 5000)
+                .build(),
+        );
+        history.push(
+            Transaction::new(date(10, 4))
+                .with_leg("grocery", -2000)
+                .build(),
+        );
+        history.push(
+            Transaction::new(date(10, 4))
+                .with_leg("grocery", -500)
+                .with_leg("car", 500)
+                .build(),
+        );
+        history.push(Transaction::new(date(10, 6)).with_leg("car", -300).build());
+        history
+    }
+
+    #[test]
+    fn rebuilds_daily_balances() {
+        let fund = Fund::new().with_amount(4000).build();
+        let balances = daily_balances("grocery", &fund, &history(), date(10, 1), date(10, 5));
+        let amounts: Vec<i64> = balances.iter().map(|b| b.1).collect();
+        assert_eq!(amounts, vec![1500, 6500, 6500, 4000, 4000]);
+        assert_eq!(balances[0].0, date(10, 1));
+        assert_eq!(balances[4].0, date(10, 5));
+
+        let balances = daily_balances("grocery", &fund, &history(), date(9, 30), date(10, 2));
+        let amounts: Vec<i64> = balances.iter().map(|b| b.1).collect();
+        assert_eq!(amounts, vec![1500, 1500, 6500]);
+    }
+
+    #[test]
+    fn sums_monthly_spending() {
+        let spending = monthly_spending("grocery", &history(), 3, date(10, 18));
+        assert_eq!(
+            spending,
+            vec![(date(8, 1), 0), (date(9, 1), 1000), (date(10, 1), 2000)]
+        );
+    }
+
+    #[test]
+    fn sums_spending_by_fund() {
+        let spending = spending_by_fund(&history(), date(10, 1), date(10, 31));
+        assert_eq!(
+            spending,
+            vec![(String::from("grocery"), 2000), (String::from("car"), 300)]
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use std::fs;
+use std::io;
+use std::io::prelude::*;
+use std::path::Path;
+
+use libfund::FundManagerError;
+
+use cli;
+use hooks;
+use {execute_line, print_dry_run, Config, Data};
+
+/// Runs the commands in the batch file named by `config`, or standard
+/// input, one per line, saving the funds once at the end, or printing the
+/// changes on a dry run. Blank lines and lines starting with `#` are
+/// skipped.
+///
+/// Unless `config.continue_on_error` is set, nothing is saved when a line
+/// fails. Otherwise failing lines are reported and left out while the
+/// rest are saved. Commands that write other files, such as
+/// `profile create` or `report --html`, are refused so a failing line
+/// can't leave them behind.
+///
+/// # Errors
+///
+/// * When the batch or the funds could not be read, or the funds could
+///   not be saved
+/// * When a line fails, naming the line
+pub fn run(config: &Config) -> Result<(), FundManagerError> {
+    let text = match config.batch_file {
+        Some(ref path) if path != Path::new("-") => fs::read_to_string(path)?,
+        _ => {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text)?;
+            text
+        }
+    };
+    let mut data = Data::load(config)?;
+    let before = data.clone();
+
+    let mut commands = 0;
+    let mut failed = 0;
+    for (index, line) in text.lines().enumerate() {
+        let words = match cli::split_words(line) {
+            Ok(ref words) if words.is_empty() || words[0].starts_with('#') => continue,
+            words => words,
+        };
+        commands += 1;
+        match words.and_then(|words| execute_line(config, &words, &data)) {
+            Ok(next) => data = next,
+            Err(e) if config.continue_on_error => {
+                eprintln!("line {}: {}", index + 1, e);
+                failed += 1;
+            }
+            Err(e) => {
+                return Err(From::from(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("line {}: {}. Nothing was saved", index + 1, e),
+                )))
+            }
+        }
+    }
+    if config.dry_run {
+        print_dry_run(&before, &data, &config.style);
+    } else {
+        data.save(config)?;
+        print!("{}", hooks::exit(config, "batch", &before, &data)?);
+    }
+
+    if failed > 0 {
+        return Err(From::from(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} of {} commands failed", failed, commands),
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+
+    use cli;
+    use Config;
+
+    #[test]
+    fn failing_batches_leave_nothing_behind() {
+        let dir = env::temp_dir().join("fund-batch-test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        env::set_var("XDG_CONFIG_HOME", dir.join("config"));
+        env::set_var("XDG_DATA_HOME", dir.join("data"));
+        let fundfile = dir.join("funds");
+        let report = dir.join("report.html");
+        let batch = dir.join("batch");
+
+        for command in &[
+            String::from("profile create holiday"),
+            format!("report --html {}", report.display()),
+            String::from("spend nothing 5"),
+        ] {
+            fs::write(&batch, format!("new grocery 10\n{}\n", command)).unwrap();
+            let matches = cli::app().get_matches_from(vec![
+                "fund",
+                "--fundfile",
+                fundfile.to_str().unwrap(),
+                "batch",
+                batch.to_str().unwrap(),
+            ]);
+            let config = Config::new(&matches).unwrap();
+            assert!(super::run(&config).is_err(), "{} ran", command);
+            assert_eq!(fs::read_to_string(&fundfile).unwrap_or_default(), "");
+            assert!(!report.exists());
+            assert!(!config
+                .profiles
+                .list()
+                .unwrap()
+                .contains(&String::from("holiday")));
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,268 @@
+use std::cmp::Reverse;
+
+use chrono::{Duration, NaiveDate};
+use libfund::currency::{Currency, NoRateError, Rates};
+use libfund::history::History;
+use libfund::money::MoneyFormat;
+use libfund::{timeseries, Fund, FundManager};
+
+use report;
+use term;
+use term::{Color, Style};
+
+/// How many lines tall line charts are
+const CHART_HEIGHT: usize = 10;
+
+/// How many months the spending chart of a single fund covers
+const SPENDING_MONTHS: u32 = 6;
+
+/// Bits of a braille character for each dot, indexed by `[row][column]`
+const BRAILLE_DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/// Partially filled blocks, from one eighth to a full block
+const BLOCKS: [char; 8] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉', '█'];
+
+/// Returns a line chart of `values` drawn with braille characters, each
+/// of which holds 2 by 4 dots. The chart is `width` characters wide and
+/// `height` lines tall, with the largest value at the top.
+pub fn line_chart(values: &[i64], width: usize, height: usize) -> Vec<String> {
+    let dots_wide = width * 2;
+    let dots_high = height * 4;
+    let mut cells = vec![vec![0u32; width]; height];
+    if values.is_empty() || width == 0 || height == 0 {
+        return cells.iter().map(|_| " ".repeat(width)).collect();
+    }
+
+    let min = *values.iter().min().unwrap_or(&0);
+    let max = *values.iter().max().unwrap_or(&0);
+    let row_of = |value: i64| -> usize {
+        if max == min {
+            dots_high / 2
+        } else {
+            let fraction = (max - value) as f64 / (max - min) as f64;
+            (fraction * (dots_high - 1) as f64).round() as usize
+        }
+    };
+
+    let mut previous: Option<usize> = None;
+    for x in 0..dots_wide {
+        let index = if dots_wide > 1 {
+            x * (values.len() - 1) / (dots_wide - 1)
+        } else {
+            0
+        };
+        let y = row_of(values[index]);
+        let (top, bottom) = match previous {
+            Some(previous) if previous < y => (previous + 1, y),
+            Some(previous) if previous > y => (y, previous - 1),
+            _ => (y, y),
+        };
+        for dot in top..=bottom {
+            cells[dot / 4][x / 2] |= BRAILLE_DOTS[dot % 4][x % 2];
+        }
+        previous = Some(y);
+    }
+
+    cells
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|&bits| std::char::from_u32(0x2800 + bits).unwrap_or(' '))
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns a horizontal bar `width` characters wide at most, as long as
+/// `value` is compared to `max`, using eighth blocks for the last character
+pub fn bar(value: i64, max: i64, width: usize) -> String {
+    if max <= 0 || value <= 0 {
+        return String::new();
+    }
+    let eighths = (value as f64 / max as f64 * (width * 8) as f64).round() as usize;
+    let eighths = eighths.clamp(1, width * 8);
+    let mut bar = BLOCKS[7].to_string().repeat(eighths / 8);
+    let partial = eighths % 8;
+    if partial > 0 {
+        bar.push(BLOCKS[partial - 1]);
+    }
+    bar
+}
+
+/// Returns one line per row, each holding the row's label, a bar sized
+/// relative to the largest value and the row's text, fitted into `width`
+/// characters
+pub fn bar_chart(rows: &[(String, i64, String)], width: usize) -> Vec<String> {
+    let label_width = rows
+        .iter()
+        .map(|row| term::width(&row.0))
+        .max()
+        .unwrap_or(0);
+    let text_width = rows
+        .iter()
+        .map(|row| term::width(&row.2))
+        .max()
+        .unwrap_or(0);
+    let bar_width = width.saturating_sub(label_width + text_width + 4).max(1);
+    let max = rows.iter().map(|row| row.1).max().unwrap_or(0);
+    rows.iter()
+        .map(|&(ref label, value, ref text)| {
+            let bar = bar(value, max, bar_width);
+            format!(
+                "{}{}  {}{}  {}",
+                label,
+                " ".repeat(label_width - term::width(label)),
+                bar,
+                " ".repeat(bar_width - term::width(&bar)),
+                text
+            )
+        })
+        .collect()
+}
+
+/// Prints a chart of the balance of the fund called `name` over the last
+/// `days` days, followed by its spending in each of the last few months
+pub fn print_fund_graphs(
+    name: &str,
+    fund: &Fund,
+    history: &History,
+    days: i64,
+    today: NaiveDate,
+    style: &Style,
+) {
+    let width = term::terminal_width();
+    let money = style.money_for(fund.currency);
+    let from = today - Duration::days(days.max(1) - 1);
+    let balances: Vec<i64> = timeseries::daily_balances(name, fund, history, from, today)
+        .iter()
+        .map(|balance| balance.1)
+        .collect();
+    let title = format!("Balance of {} over the last {} days", name, days);
+    print_balance_chart(&title, &balances, from, today, &money, style);
+
+    println!();
+    println!(
+        "{}",
+        term::paint("Monthly spending", Color::Bold, style.color)
+    );
+    let rows: Vec<(String, i64, String)> =
+        timeseries::monthly_spending(name, history, SPENDING_MONTHS, today)
+            .iter()
+            .map(|&(month, spent)| {
+                (
+                    month.format("%b %Y").to_string(),
+                    spent,
+                    money.format(spent),
+                )
+            })
+            .collect();
+    for line in bar_chart(&rows, width) {
+        println!("{}", line);
+    }
+}
+
+/// Prints a chart of `balances`, one for each day from `from` to `to`,
+/// below `title`
+pub fn print_balance_chart(
+    title: &str,
+    balances: &[i64],
+    from: NaiveDate,
+    to: NaiveDate,
+    money: &MoneyFormat,
+    style: &Style,
+) {
+    let max = money.format(*balances.iter().max().unwrap_or(&0));
+    let min = money.format(*balances.iter().min().unwrap_or(&0));
+    let axis_width = term::width(&max).max(term::width(&min));
+    let chart_width = term::terminal_width()
+        .saturating_sub(axis_width + 2)
+        .max(10);
+
+    println!("{}", term::paint(title, Color::Bold, style.color));
+    let lines = line_chart(balances, chart_width, CHART_HEIGHT);
+    for (index, line) in lines.iter().enumerate() {
+        let label = if index == 0 {
+            max.as_str()
+        } else if index + 1 == lines.len() {
+            min.as_str()
+        } else {
+            ""
+        };
+        println!("{:>width$} ┤{}", label, line, width = axis_width);
+    }
+    println!(
+        "{:>width$} └{}",
+        "",
+        "─".repeat(chart_width),
+        width = axis_width
+    );
+    let start = from.format("%Y-%m-%d").to_string();
+    let end = to.format("%Y-%m-%d").to_string();
+    println!(
+        "{:>width$}  {}{:>gap$}",
+        "",
+        start,
+        end,
+        width = axis_width,
+        gap = chart_width.saturating_sub(start.len())
+    );
+}
+
+/// Prints how close every fund is to its goal, followed by how much was
+/// spent from each fund since `from`, converted into `currency`
+///
+/// # Errors
+///
+/// * When a fund's currency has no exchange rate to `currency`
+pub fn print_overview(
+    funds: &FundManager,
+    history: &History,
+    rates: &Rates,
+    currency: Currency,
+    from: NaiveDate,
+    today: NaiveDate,
+    style: &Style,
+) -> Result<(), NoRateError> {
+    let width = term::terminal_width();
+    let name_width = funds
+        .into_iter()
+        .map(|(name, _)| term::width(name))
+        .max()
+        .unwrap_or(0);
+    let bar_width = width.saturating_sub(name_width + 8).max(10);
+
+    println!("{}", term::paint("Goal progress", Color::Bold, style.color));
+    for (name, fund) in funds {
+        let color = report::status_color(fund);
+        let bar = term::progress_bar(fund.progress(), bar_width);
+        println!(
+            "{:<name_width$}  {} {:>4.0}%",
+            name,
+            term::paint(&bar, color, style.color),
+            fund.progress() * 100.0,
+            name_width = name_width
+        );
+    }
+
+    println!();
+    let title = format!("Spending since {}", from.format("%Y-%m-%d"));
+    println!("{}", term::paint(&title, Color::Bold, style.color));
+    let money = style.money_for(currency);
+    let mut rows: Vec<(String, i64, String)> = Vec::new();
+    for (name, spent) in timeseries::spending_by_fund(history, from, today) {
+        let fund_currency = funds
+            .fund(&name)
+            .map(|fund| fund.currency)
+            .unwrap_or(currency);
+        let spent = rates.convert(spent, fund_currency, currency)?;
+        rows.push((name, spent, money.format(spent)));
+    }
+    rows.sort_by_key(|row| Reverse(row.1));
+    if rows.is_empty() {
+        println!("Nothing spent");
+    }
+    for line in bar_chart(&rows, width) {
+        println!("{}", line);
+    }
+    Ok(())
+}
@@ -0,0 +1,602 @@
+//! The command line interface, shared by the `fund` binary and the
+//! interactive shell
+
+use clap::{App, AppSettings, Arg, ArgGroup, ErrorKind, SubCommand};
+
+use settings::Settings;
+use Config;
+
+/// The name of every command, which aliases can't replace
+pub const COMMANDS: &[&str] = &[
+    "alerts",
+    "batch",
+    "completions",
+    "deposit",
+    "export",
+    "forecast",
+    "graph",
+    "import",
+    "info",
+    "new",
+    "profile",
+    "rate",
+    "rename",
+    "report",
+    "set",
+    "shell",
+    "spend",
+    "stats",
+    "transfer",
+    "tui",
+];
+
+/// Returns the argument parser for every `fund` command
+pub fn app() -> App<'static, 'static> {
+    App::new("fundwarrior")
+                        .version("0.8.1")
+                        .author("Christopher Leggett <leggettc18@gmail.com>")
+                        .about("Simple CLI Money Management")
+                        .global_setting(AppSettings::InferSubcommands)
+                        .arg(Arg::with_name("fundfile")
+                            .short("f")
+                            .long("fundfile")
+                            .value_name("FILE")
+                            .help("Sets a custom fund file")
+                            .takes_value(true))
+                        .arg(Arg::with_name("profile")
+                            .long("profile")
+                            .value_name("NAME")
+                            .help("Uses the funds, history and settings of a named profile")
+                            .takes_value(true))
+                        .arg(Arg::with_name("dry-run")
+                            .long("dry-run")
+                            .help("Shows what a command would change without saving anything"))
+                        .arg(Arg::with_name("verbose")
+                            .short("v")
+                            .long("verbose")
+                            .help("Enables verbose output"))
+                        .subcommand(SubCommand::with_name("new")
+                            .about("Creates a new fund")
+                            .arg(Arg::with_name("name")
+                                .help("The name of the fund to create")
+                                .required(true))
+                            .arg(Arg::with_name("amount")
+                                .help("The amount to start the fund with")
+                                .required(false))
+                            .arg(Arg::with_name("goal")
+                                .help("The amount you want this fund to have in the future")
+                                .required(false))
+                            .arg(Arg::with_name("currency")
+                                .long("currency")
+                                .value_name("CODE")
+                                .help("The ISO 4217 currency the fund is kept in, e.g. EUR")
+                                .takes_value(true)))
+                        .subcommand(SubCommand::with_name("deposit")
+                            .about("Deposit money into a fund")
+                            .arg(Arg::with_name("name")
+                                .help("The name of the fund you are depositing into")
+                                .required(true))
+                            .arg(Arg::with_name("amount")
+                                .help("The amount you wish to deposit")
+                                .required(true))
+                            .args(&transaction_args()))
+                        .subcommand(SubCommand::with_name("spend")
+                            .about("Spend money from a fund")
+                            .arg(Arg::with_name("name")
+                                .help("The name of the fund you are spending from")
+                                .required_unless("split"))
+                            .arg(Arg::with_name("amount")
+                                .help("The amount you are spending")
+                                .required_unless("split"))
+                            .arg(Arg::with_name("split")
+                                .long("split")
+                                .value_name("FUND=AMOUNT")
+                                .help("Spends from several funds in one transaction, e.g. --split grocery=82.10 gifts=25.50")
+                                .multiple(true)
+                                .conflicts_with_all(&["name", "amount"]))
+                            .args(&transaction_args()))
+                        .subcommand(SubCommand::with_name("info")
+                            .about("View fund information")
+                            .arg(Arg::with_name("name")
+                                .help("The name of the fund you wish to view. If absent, all funds will be printed.")
+                                .required(false))
+                            .arg(Arg::with_name("sort")
+                                .short("s")
+                                .long("sort")
+                                .value_name("KEY")
+                                .help("Orders the funds by the given key")
+                                .possible_values(&["name", "amount", "goal", "remaining", "progress"])
+                                .takes_value(true))
+                            .arg(Arg::with_name("reverse")
+                                .short("r")
+                                .long("reverse")
+                                .help("Reverses the sort order"))
+                            .arg(Arg::with_name("columns")
+                                .short("c")
+                                .long("columns")
+                                .value_name("LIST")
+                                .help("Comma separated columns to show: name, amount, goal, remaining, progress, summary")
+                                .takes_value(true))
+                            .arg(Arg::with_name("report")
+                                .long("report")
+                                .value_name("NAME")
+                                .help("Uses the columns and sorting of a report defined in the config file")
+                                .takes_value(true))
+                            .arg(Arg::with_name("currency")
+                                .long("currency")
+                                .value_name("CODE")
+                                .help("The currency to report totals in")
+                                .takes_value(true)))
+                        .subcommand(SubCommand::with_name("transfer")
+                            .about("Transfer money between funds")
+                            .arg(Arg::with_name("from_name")
+                                .help("The name of the fund you wish to transfer money out of")
+                                .required(true))
+                            .arg(Arg::with_name("to_name")
+                                .help("The name of the fund you wish to transfer money to")
+                                .required(true))
+                            .arg(Arg::with_name("amount")
+                                .help("The amount you wish to transfer")
+                                .required(true))
+                            .args(&transaction_args()))
+                        .subcommand(SubCommand::with_name("rename")
+                            .about("Rename a fund")
+                            .arg(Arg::with_name("old_name")
+                                .help("The name of the fund you wish to rename")
+                                .required(true))
+                            .arg(Arg::with_name("new_name")
+                                .help("The unique name you wish to give the fund")
+                                .required(true)))
+                        .subcommand(SubCommand::with_name("set")
+                            .about("Set the amount or goal of a fund")
+                            .arg(Arg::with_name("name")
+                                .help("The name of the fund you wish to update")
+                                .required(true))
+                            .arg(Arg::with_name("field")
+                                .help("The name of the field you wish to change (either amount or goal)")
+                                .required(true))
+                            .arg(Arg::with_name("amount")
+                                .help("The amount you wish to change the specied field to")
+                                .required(true)))
+                        .subcommand(SubCommand::with_name("stats")
+                            .about("View spending statistics")
+                            .arg(Arg::with_name("name")
+                                .help("The name of the fund you wish to view. If absent, all funds will be shown.")
+                                .required(false))
+                            .arg(Arg::with_name("period")
+                                .short("p")
+                                .long("period")
+                                .value_name("PERIOD")
+                                .help("The period to gather statistics over, compared to the one before it")
+                                .possible_values(&["month", "quarter", "year"])
+                                .default_value("month")
+                                .takes_value(true)))
+                        .subcommand(SubCommand::with_name("alerts")
+                            .about("List overdrawn funds, funds below their alert and funds that just reached their goal")
+                            .after_help("Exits with status 2 when there are alerts, for use in scripts."))
+                        .subcommand(SubCommand::with_name("forecast")
+                            .about("Project balances from scheduled transactions and recent spending")
+                            .arg(Arg::with_name("name")
+                                .help("The fund to chart the forecast of. If absent, all funds are listed.")
+                                .required(false))
+                            .arg(Arg::with_name("until")
+                                .short("u")
+                                .long("until")
+                                .value_name("DATE")
+                                .help("The last day to forecast, as YYYY-MM-DD. Defaults to 90 days from today.")
+                                .takes_value(true)))
+                        .subcommand(SubCommand::with_name("graph")
+                            .about("Chart balances, spending and progress towards goals")
+                            .arg(Arg::with_name("name")
+                                .help("The fund to chart the balance and spending of. If absent, all funds are compared.")
+                                .required(false))
+                            .arg(Arg::with_name("days")
+                                .short("d")
+                                .long("days")
+                                .value_name("DAYS")
+                                .help("How many days of balances to chart, from 1 to 3660")
+                                .default_value("90")
+                                .takes_value(true)))
+                        .subcommand(SubCommand::with_name("report")
+                            .about("Write a standalone report of this month's funds and spending")
+                            .arg(Arg::with_name("html")
+                                .long("html")
+                                .value_name("FILE")
+                                .help("Write the report as an HTML page")
+                                .takes_value(true))
+                            .arg(Arg::with_name("svg")
+                                .long("svg")
+                                .value_name("FILE")
+                                .help("Write the report as an SVG image")
+                                .takes_value(true))
+                            .group(ArgGroup::with_name("output")
+                                .args(&["html", "svg"])
+                                .multiple(true)
+                                .required(true))
+                            .arg(Arg::with_name("currency")
+                                .long("currency")
+                                .value_name("CODE")
+                                .help("The currency to show totals and spending in")
+                                .takes_value(true)))
+                        .subcommand(SubCommand::with_name("import")
+                            .about("Post bank statements to funds")
+                            .subcommand(SubCommand::with_name("csv")
+                                .about("Import a CSV statement laid out as described by a profile")
+                                .arg(Arg::with_name("file")
+                                    .help("The statement to import")
+                                    .required(true))
+                                .arg(Arg::with_name("profile")
+                                    .long("profile")
+                                    .value_name("BANK")
+                                    .help("The profile in import/profiles describing the bank's columns")
+                                    .required(true)
+                                    .takes_value(true)))
+                            .subcommand(SubCommand::with_name("ofx")
+                                .about("Import an OFX or QFX statement")
+                                .alias("qfx")
+                                .arg(Arg::with_name("file")
+                                    .help("The statement to import")
+                                    .required(true)))
+                            .subcommand(SubCommand::with_name("json")
+                                .about("Restore a backup written by 'fund export json'")
+                                .arg(Arg::with_name("file")
+                                    .help("The backup to import")
+                                    .required(true))
+                                .arg(Arg::with_name("merge")
+                                    .long("merge")
+                                    .value_name("STRATEGY")
+                                    .help("Add the backup to the existing funds, settling funds whose names are taken by skipping, overwriting, summing or renaming them, or stopping with an error")
+                                    .possible_values(&["skip", "overwrite", "sum", "rename", "error"])
+                                    .conflicts_with("replace")
+                                    .takes_value(true))
+                                .arg(Arg::with_name("replace")
+                                    .long("replace")
+                                    .help("Replace all funds, history and exchange rates with the backup")))
+                            .subcommand(SubCommand::with_name("qif")
+                                .about("Import a QIF statement")
+                                .arg(Arg::with_name("file")
+                                    .help("The statement to import")
+                                    .required(true))
+                                .arg(Arg::with_name("day-first")
+                                    .long("day-first")
+                                    .help("Read dates as day before month, e.g. 03/10/2026 for 3 October"))))
+                        .subcommand(SubCommand::with_name("export")
+                            .about("Write funds and their history as a plain-text accounting journal or a JSON backup")
+                            .arg(Arg::with_name("format")
+                                .help("The journal format to write")
+                                .possible_values(&["ledger", "hledger", "beancount", "json"])
+                                .required(true))
+                            .arg(Arg::with_name("output")
+                                .short("o")
+                                .long("output")
+                                .value_name("FILE")
+                                .help("Write to a file instead of standard output")
+                                .takes_value(true)))
+                        .subcommand(SubCommand::with_name("profile")
+                            .about("Manage named profiles, each with its own funds, history and settings")
+                            .subcommand(SubCommand::with_name("list")
+                                .about("List all profiles, marking the one in use"))
+                            .subcommand(SubCommand::with_name("create")
+                                .about("Create an empty profile")
+                                .arg(Arg::with_name("name")
+                                    .help("The name of the profile to create")
+                                    .required(true)))
+                            .subcommand(SubCommand::with_name("use")
+                                .about("Use a profile whenever --profile is not given")
+                                .arg(Arg::with_name("name")
+                                    .help("The profile to use, or 'default'")
+                                    .required(true))))
+                        .subcommand(SubCommand::with_name("batch")
+                            .about("Runs commands from a file, one per line, saving them all or none")
+                            .arg(Arg::with_name("file")
+                                .help("The file to read commands from, or - for standard input")
+                                .required(false))
+                            .arg(Arg::with_name("continue-on-error")
+                                .long("continue-on-error")
+                                .help("Skips failing commands and saves the others")))
+                        .subcommand(SubCommand::with_name("shell")
+                            .about("Runs commands interactively, saving them all at once"))
+                        .subcommand(SubCommand::with_name("tui")
+                            .about("Browses and changes funds in a full screen interface"))
+                        .subcommand(SubCommand::with_name("completions")
+                            .about("Prints a script that completes commands and fund names in your shell")
+                            .arg(Arg::with_name("shell")
+                                .help("The shell to complete in")
+                                .possible_values(&["bash", "zsh", "fish"])
+                                .required(true)))
+                        .subcommand(SubCommand::with_name("_names")
+                            .setting(AppSettings::Hidden)
+                            .about("Lists names for shell completion, one per line")
+                            .arg(Arg::with_name("kind")
+                                .possible_values(&["funds", "fields", "profiles"])
+                                .default_value("funds")))
+                        .subcommand(SubCommand::with_name("rate")
+                            .about("Manage exchange rates between currencies")
+                            .subcommand(SubCommand::with_name("set")
+                                .about("Set how much one unit of a currency is worth in another")
+                                .arg(Arg::with_name("from")
+                                    .help("The currency being converted from, e.g. EUR")
+                                    .required(true))
+                                .arg(Arg::with_name("to")
+                                    .help("The currency being converted to, e.g. USD")
+                                    .required(true))
+                                .arg(Arg::with_name("rate")
+                                    .help("How many units of the second currency one unit of the first is worth")
+                                    .required(true)))
+                            .subcommand(SubCommand::with_name("list")
+                                .about("List all exchange rates")))
+}
+
+/// Returns the options describing the transaction recorded by `spend`,
+/// `deposit` and `transfer`
+fn transaction_args() -> Vec<Arg<'static, 'static>> {
+    vec![
+        Arg::with_name("memo")
+            .long("memo")
+            .value_name("TEXT")
+            .help("A note about what the money was for")
+            .takes_value(true),
+        Arg::with_name("payee")
+            .long("payee")
+            .value_name("NAME")
+            .help("Who the money was paid to or received from")
+            .takes_value(true),
+        Arg::with_name("date")
+            .long("date")
+            .value_name("DATE")
+            .help("When it happened: YYYY-MM-DD, today, yesterday or a number of days ago like -3d")
+            .takes_value(true)
+            .allow_hyphen_values(true),
+    ]
+}
+
+/// Parses a line of the shell or of a batch file, already split into
+/// words, as a `fund` command using the profile and fund file of `base`,
+/// and as a dry run when `base` is one. Returns `None` when the line only
+/// asked for help, which is printed.
+///
+/// # Errors
+///
+/// * When the words are not a valid command or have invalid values
+pub fn parse_line(base: &Config, words: &[String]) -> Result<Option<Config>, String> {
+    let mut args = vec![
+        String::from("fund"),
+        String::from("--profile"),
+        base.profile.clone(),
+        String::from("--fundfile"),
+        base.fundfile.to_string_lossy().into_owned(),
+    ];
+    if base.dry_run {
+        args.push(String::from("--dry-run"));
+    }
+    args.extend(words.iter().cloned());
+    let args = expand_alias(args, &base.settings)?;
+
+    match app().get_matches_from_safe(args) {
+        Ok(matches) => Config::new(&matches).map(Some).map_err(|e| e.to_string()),
+        Err(ref e)
+            if e.kind == ErrorKind::HelpDisplayed || e.kind == ErrorKind::VersionDisplayed =>
+        {
+            println!("{}", e.message);
+            Ok(None)
+        }
+        Err(e) => {
+            let message = e.message.lines().next().unwrap_or_default();
+            Err(String::from(message.trim_start_matches("error: ")))
+        }
+    }
+}
+
+/// Returns the position of the command in `args`, the first word after
+/// the program name that is neither an option nor the value of one
+fn command_index(args: &[String]) -> Option<usize> {
+    let mut index = 1;
+    while index < args.len() {
+        match args[index].as_str() {
+            "-f" | "--fundfile" | "--profile" => index += 2,
+            arg if arg.starts_with('-') => index += 1,
+            _ => return Some(index),
+        }
+    }
+    None
+}
+
+/// Returns the profile given with `--profile` before the command in `args`
+pub fn profile_arg(args: &[String]) -> Option<&str> {
+    let end = command_index(args).unwrap_or(args.len());
+    args[..end]
+        .iter()
+        .enumerate()
+        .rev()
+        .find_map(|(index, arg)| match arg.as_str() {
+            "--profile" => args.get(index + 1).map(String::as_str),
+            _ => arg.strip_prefix("--profile="),
+        })
+}
+
+/// Replaces the command in `args` with the words of the `alias.<command>`
+/// setting, if there is one, so `alias.gas = spend car` turns
+/// `fund gas 30.00` into `fund spend car 30.00`. Commands are never
+/// replaced, and aliases are not expanded again.
+///
+/// # Errors
+///
+/// * When the alias has an unclosed quote or ends with a backslash
+pub fn expand_alias(mut args: Vec<String>, settings: &Settings) -> Result<Vec<String>, String> {
+    let index = match command_index(&args) {
+        Some(index) => index,
+        None => return Ok(args),
+    };
+    if COMMANDS.contains(&args[index].as_str()) {
+        return Ok(args);
+    }
+    if let Some(alias) = settings.get(&format!("alias.{}", args[index])) {
+        let words = split_words(alias).map_err(|e| format!("alias '{}': {}", args[index], e))?;
+        args.splice(index..=index, words);
+    }
+    Ok(args)
+}
+
+/// Returns every option of `app()` that takes a value, such as `-d` and
+/// `--days`, so completions can skip the word after them
+pub fn value_options() -> Vec<String> {
+    // clap 2 has no public way to list arguments, but its parser is exposed
+    fn collect(app: &App, options: &mut Vec<String>) {
+        for option in &app.p.opts {
+            options.extend(option.s.short.map(|short| format!("-{}", short)));
+            options.extend(option.s.long.map(|long| format!("--{}", long)));
+        }
+        for subcommand in &app.p.subcommands {
+            collect(subcommand, options);
+        }
+    }
+    let mut options = Vec::new();
+    collect(&app(), &mut options);
+    options.sort_unstable();
+    options.dedup();
+    options
+}
+
+/// Splits a line into words at whitespace. Single and double quotes group
+/// words containing spaces, and a backslash takes the next character
+/// literally.
+///
+/// # Errors
+///
+/// * When a quote is not closed or the line ends with a backslash
+pub fn split_words(line: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word = None;
+    let mut quote = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match (c, quote) {
+            ('\\', Some('\'')) => word.get_or_insert_with(String::new).push(c),
+            ('\\', _) => match chars.next() {
+                Some(next) => word.get_or_insert_with(String::new).push(next),
+                None => return Err(String::from("the line ends with a backslash")),
+            },
+            (c, Some(q)) if c == q => quote = None,
+            ('\'', None) | ('"', None) => {
+                word.get_or_insert_with(String::new);
+                quote = Some(c);
+            }
+            (c, None) if c.is_whitespace() => words.extend(word.take()),
+            (c, _) => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if quote.is_some() {
+        return Err(String::from("a quote is not closed"));
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{command_index, expand_alias, profile_arg, split_words};
+    use settings::Settings;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn splits_words() {
+        assert_eq!(
+            split_words("  spend  grocery 12.50 ").unwrap(),
+            ["spend", "grocery", "12.50"]
+        );
+        assert_eq!(
+            split_words(r#"new "car repairs" 'it''s' "" a\ b"#).unwrap(),
+            ["new", "car repairs", "its", "", "a b"]
+        );
+        assert_eq!(
+            split_words(r#"'C:\funds' "say \"hi\"" \'"#).unwrap(),
+            [r"C:\funds", r#"say "hi""#, "'"]
+        );
+        assert!(split_words("spend 'grocery").is_err());
+        assert!(split_words("spend grocery\\").is_err());
+        assert!(split_words("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn finds_the_command() {
+        assert_eq!(command_index(&args("fund spend car 1.00")), Some(1));
+        assert_eq!(
+            command_index(&args("fund -f funds --profile work --dry-run spend")),
+            Some(6)
+        );
+        assert_eq!(command_index(&args("fund --profile=work spend")), Some(2));
+        assert_eq!(command_index(&args("fund --dry-run")), None);
+    }
+
+    #[test]
+    fn finds_the_profile() {
+        assert_eq!(
+            profile_arg(&args("fund --profile work spend")),
+            Some("work")
+        );
+        assert_eq!(
+            profile_arg(&args("fund --profile=work spend")),
+            Some("work")
+        );
+        assert_eq!(
+            profile_arg(&args("fund --profile home --profile=work info")),
+            Some("work")
+        );
+        assert_eq!(profile_arg(&args("fund profile use --profile work")), None);
+        assert_eq!(profile_arg(&args("fund --profile")), None);
+        assert_eq!(profile_arg(&args("fund info")), None);
+    }
+
+    #[test]
+    fn expands_aliases() {
+        let mut settings = Settings::new();
+        settings.set("alias.gas", "spend car --memo 'gas station'");
+        settings.set("alias.spend", "deposit");
+        settings.set("alias.loop", "loop again");
+        settings.set("alias.bad", "spend 'car");
+
+        assert_eq!(
+            expand_alias(args("fund gas 30.00"), &settings).unwrap(),
+            ["fund", "spend", "car", "--memo", "gas station", "30.00"]
+        );
+        assert_eq!(
+            expand_alias(args("fund --profile work -f funds gas 30.00"), &settings).unwrap(),
+            [
+                "fund",
+                "--profile",
+                "work",
+                "-f",
+                "funds",
+                "spend",
+                "car",
+                "--memo",
+                "gas station",
+                "30.00"
+            ]
+        );
+        assert_eq!(
+            expand_alias(args("fund --dry-run gas"), &settings).unwrap()[2],
+            "spend"
+        );
+        assert_eq!(
+            expand_alias(args("fund info gas"), &settings).unwrap(),
+            args("fund info gas")
+        );
+        assert_eq!(
+            expand_alias(args("fund spend car 1.00"), &settings).unwrap(),
+            args("fund spend car 1.00")
+        );
+        assert_eq!(
+            expand_alias(args("fund loop"), &settings).unwrap(),
+            args("fund loop again")
+        );
+        assert_eq!(
+            expand_alias(args("fund --profile=work"), &settings).unwrap(),
+            args("fund --profile=work")
+        );
+        assert!(expand_alias(args("fund bad"), &settings).is_err());
+    }
+}
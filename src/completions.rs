@@ -0,0 +1,225 @@
+//! Completion scripts for bash, zsh and fish. The scripts complete fund
+//! names by calling the hidden `fund _names` command, passing on any
+//! `--fundfile` or `--profile` given before the command, so they always
+//! match the funds that command would use.
+
+use std::io;
+
+use libfund::FundManager;
+
+use cli::{self, COMMANDS};
+use Config;
+
+/// The fields `fund set` can change
+pub const FIELDS: &[&str] = &["amount", "goal"];
+
+const BASH: &str = r#"# bash completion for fund
+_fund() {
+    local cur=${COMP_WORDS[COMP_CWORD]} prev=${COMP_WORDS[COMP_CWORD-1]}
+    local command="" position=0 global=() i
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        case ${COMP_WORDS[i]} in
+            -f|--fundfile|--profile)
+                [[ -z $command ]] && global+=("${COMP_WORDS[i]}" "${COMP_WORDS[i+1]}")
+                ((i++)) ;;
+            @VALUE_OPTIONS@) ((i++)) ;;
+            -*) ;;
+            *) if [[ -z $command ]]; then command=${COMP_WORDS[i]}; else ((position++)); fi ;;
+        esac
+    done
+
+    local IFS=$'\n' candidates=""
+    if [[ -z $command && $prev == --profile ]]; then
+        candidates=$(fund _names profiles 2>/dev/null)
+    elif [[ $cur == -* || $prev == @VALUE_PATTERN@ ]]; then
+        return
+    elif [[ -z $command ]]; then
+        candidates=$'@COMMANDS@'
+    else
+        case $command:$position in
+            info:0|spend:0|deposit:0|transfer:0|transfer:1|rename:0|set:0|stats:0|graph:0|forecast:0)
+                candidates=$(fund "${global[@]}" _names funds 2>/dev/null) ;;
+            set:1) candidates=$(fund _names fields 2>/dev/null) ;;
+            completions:0) candidates=$'bash\nzsh\nfish' ;;
+        esac
+    fi
+    COMPREPLY=($(compgen -W "$candidates" -- "$cur"))
+    COMPREPLY=("${COMPREPLY[@]// /\\ }")
+}
+complete -F _fund fund
+"#;
+
+const ZSH: &str = r#"#compdef fund
+# zsh completion for fund
+_fund() {
+    local command="" position=0 i
+    local -a global candidates
+    for ((i = 2; i < CURRENT; i++)); do
+        case ${words[i]} in
+            -f|--fundfile|--profile)
+                [[ -z $command ]] && global+=(${words[i]} ${words[i+1]})
+                ((i++)) ;;
+            @VALUE_OPTIONS@) ((i++)) ;;
+            -*) ;;
+            *) if [[ -z $command ]]; then command=${words[i]}; else ((position++)); fi ;;
+        esac
+    done
+
+    local prev=${words[CURRENT-1]}
+    if [[ -z $command && $prev == --profile ]]; then
+        candidates=(${(f)"$(fund _names profiles 2>/dev/null)"})
+    elif [[ ${words[CURRENT]} == -* || $prev == (@VALUE_OPTIONS@) ]]; then
+        return 1
+    elif [[ -z $command ]]; then
+        candidates=(${(f)"$(print -r -- $'@COMMANDS@')"})
+    else
+        case $command:$position in
+            info:0|spend:0|deposit:0|transfer:0|transfer:1|rename:0|set:0|stats:0|graph:0|forecast:0)
+                candidates=(${(f)"$(fund $global _names funds 2>/dev/null)"}) ;;
+            set:1) candidates=(${(f)"$(fund _names fields 2>/dev/null)"}) ;;
+            completions:0) candidates=(bash zsh fish) ;;
+        esac
+    fi
+    compadd -a candidates
+}
+compdef _fund fund
+"#;
+
+const FISH: &str = r#"# fish completion for fund
+function __fund_complete
+    set -l tokens (commandline -opc)
+    set -l global
+    set -l command
+    set -l position 0
+    set -l i 2
+    while test $i -le (count $tokens)
+        switch $tokens[$i]
+            case -f --fundfile --profile
+                if test -z "$command"; and test $i -lt (count $tokens)
+                    set global $global $tokens[$i] $tokens[(math $i + 1)]
+                end
+                set i (math $i + 1)
+            case @VALUE_OPTIONS_FISH@
+                set i (math $i + 1)
+            case '-*'
+            case '*'
+                if test -z "$command"
+                    set command $tokens[$i]
+                else
+                    set position (math $position + 1)
+                end
+        end
+        set i (math $i + 1)
+    end
+
+    set -l prev $tokens[-1]
+    if test -z "$command"; and test "$prev" = --profile
+        fund _names profiles 2>/dev/null
+    else if contains -- $prev @VALUE_OPTIONS_FISH@
+        return
+    else if test -z "$command"
+        printf '%s\n' @COMMANDS_FISH@
+    else
+        switch $command:$position
+            case info:0 spend:0 deposit:0 transfer:0 transfer:1 rename:0 set:0 stats:0 graph:0 forecast:0
+                fund $global _names funds 2>/dev/null
+            case set:1
+                fund _names fields 2>/dev/null
+            case completions:0
+                printf '%s\n' bash zsh fish
+        end
+    end
+end
+complete -c fund -f -a '(__fund_complete)'
+"#;
+
+/// Returns the completion script for `shell`, which is `bash`, `zsh` or
+/// `fish`
+pub fn script(shell: &str) -> Option<String> {
+    let template = match shell {
+        "bash" => BASH,
+        "zsh" => ZSH,
+        "fish" => FISH,
+        _ => return None,
+    };
+    // Options that take a value, so the word after them is not an argument
+    let options = cli::value_options();
+    let value_options = options.join("|");
+    let fish_options = options.join(" ");
+    Some(
+        template
+            .replace("@VALUE_OPTIONS@", &value_options)
+            .replace("@VALUE_PATTERN@", &format!("@({})", value_options))
+            .replace("@VALUE_OPTIONS_FISH@", &fish_options)
+            .replace("@COMMANDS@", &COMMANDS.join("\\n"))
+            .replace("@COMMANDS_FISH@", &COMMANDS.join(" ")),
+    )
+}
+
+/// Prints the names `fund _names` was asked for, one per line: the funds
+/// named by `config`, the fields of `fund set` or the profiles
+///
+/// # Errors
+///
+/// * When the fund file or the profiles could not be read
+pub fn print_names(config: &Config) -> Result<(), io::Error> {
+    match config.names.as_deref() {
+        Some("fields") => {
+            for field in FIELDS {
+                println!("{}", field);
+            }
+        }
+        Some("profiles") => {
+            for profile in config.profiles.list()? {
+                println!("{}", profile);
+            }
+        }
+        _ => {
+            for (name, _) in &FundManager::load(&config.fundfile)? {
+                println!("{}", name);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::script;
+    use cli::{self, COMMANDS};
+
+    #[test]
+    fn scripts_know_every_command_and_value_option() {
+        let app = cli::app();
+        let commands: Vec<&str> = app
+            .p
+            .subcommands
+            .iter()
+            .map(|command| command.p.meta.name.as_str())
+            .filter(|name| !name.starts_with('_'))
+            .collect();
+        let options = cli::value_options();
+        for option in &["-d", "--days", "-p", "--period", "-u", "--split"] {
+            assert!(options.iter().any(|o| o == option), "{} missing", option);
+        }
+        for shell in &["bash", "zsh", "fish"] {
+            let script = script(shell).unwrap();
+            let words: Vec<&str> = script
+                .split(|c: char| c.is_whitespace() || "|()'$".contains(c))
+                .flat_map(|word| word.split("\\n"))
+                .collect();
+            for command in &commands {
+                assert!(COMMANDS.contains(command), "{} is not in COMMANDS", command);
+                assert!(words.contains(command), "{} misses {}", shell, command);
+            }
+            for option in &options {
+                assert!(
+                    words.contains(&option.as_str()),
+                    "{} misses {}",
+                    shell,
+                    option
+                );
+            }
+        }
+    }
+}
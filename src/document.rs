@@ -0,0 +1,584 @@
+use std::fmt::Write;
+
+use chrono::{Duration, NaiveDate};
+use libfund::currency::{Currency, NoRateError, Rates};
+use libfund::history::{History, Transaction};
+use libfund::stats::Period;
+use libfund::{timeseries, Fund, FundManager};
+
+use report;
+use term::{Color, Style};
+
+/// Colors given to funds in charts, reused once they run out
+const PALETTE: [&str; 8] = [
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1", "#9c755f",
+];
+
+/// How many days the balance trend chart covers
+const TREND_DAYS: i64 = 90;
+
+const WIDTH: i64 = 720;
+
+const STYLESHEET: &str =
+    "body { font-family: sans-serif; max-width: 760px; margin: 2em auto; color: #222; }
+table { border-collapse: collapse; width: 100%; margin-bottom: 2em; }
+th, td { padding: 4px 8px; border-bottom: 1px solid #ddd; text-align: left; }
+td.money, th.money { text-align: right; font-variant-numeric: tabular-nums; }
+.negative { color: #c62828; }
+.below { color: #b8860b; }
+.met { color: #2e7d32; }";
+
+/// Everything shown in an HTML or SVG report, gathered from the funds and
+/// their history for the month containing `today`
+pub struct ReportData<'a> {
+    pub month: NaiveDate,
+    pub today: NaiveDate,
+    pub currency: Currency,
+    pub funds: Vec<(&'a str, &'a Fund)>,
+    /// The total of every fund, unless a fund has no exchange rate to
+    /// `currency`
+    pub total: Result<Fund, NoRateError>,
+    /// Spending per fund this month, converted into `currency`
+    pub spending: Vec<(String, i64)>,
+    /// Daily balances of each fund, converted into `currency`
+    pub trends: Vec<(String, Vec<i64>)>,
+    /// Funds left out of the charts because they have no exchange rate to
+    /// `currency`
+    pub unconverted: Vec<String>,
+    pub transactions: Vec<&'a Transaction>,
+    style: &'a Style,
+}
+
+impl<'a> ReportData<'a> {
+    /// Gathers the report's data, converting amounts into `currency`.
+    /// Funds whose currency has no exchange rate to `currency` are left
+    /// out of the total and the charts.
+    pub fn new(
+        funds: &'a FundManager,
+        history: &'a History,
+        rates: &Rates,
+        currency: Currency,
+        today: NaiveDate,
+        style: &'a Style,
+    ) -> ReportData<'a> {
+        let month = Period::Month.start(today);
+        let currency_of = |name: &str| {
+            funds
+                .fund(name)
+                .map(|fund| fund.currency)
+                .unwrap_or(currency)
+        };
+
+        let mut unconverted = Vec::new();
+        let mut spending = Vec::new();
+        for (name, spent) in timeseries::spending_by_fund(history, month, today) {
+            match rates.convert(spent, currency_of(&name), currency) {
+                Ok(spent) => spending.push((name, spent)),
+                Err(_) => unconverted.push(name),
+            }
+        }
+
+        let from = today - Duration::days(TREND_DAYS - 1);
+        let mut trends = Vec::new();
+        for (name, fund) in funds {
+            let balances: Result<Vec<i64>, NoRateError> =
+                timeseries::daily_balances(name, fund, history, from, today)
+                    .into_iter()
+                    .map(|(_, balance)| rates.convert(balance, fund.currency, currency))
+                    .collect();
+            match balances {
+                Ok(balances) => trends.push((name.clone(), balances)),
+                Err(_) => unconverted.push(name.clone()),
+            }
+        }
+        unconverted.sort();
+        unconverted.dedup();
+
+        ReportData {
+            month,
+            today,
+            currency,
+            funds: funds
+                .into_iter()
+                .map(|(name, fund)| (name.as_str(), fund))
+                .collect(),
+            total: funds.total(currency, rates),
+            spending,
+            trends,
+            unconverted,
+            transactions: history
+                .iter()
+                .filter(|t| t.date >= month && t.date <= today)
+                .collect(),
+            style,
+        }
+    }
+
+    fn money(&self, amount: i64, currency: Currency) -> String {
+        self.style.money_for(currency).format(amount)
+    }
+
+    /// Returns the currency of the fund called `name`, or the report's
+    /// currency for funds that no longer exist
+    fn currency_of(&self, name: &str) -> Currency {
+        self.funds
+            .iter()
+            .find(|fund| fund.0 == name)
+            .map_or(self.currency, |fund| fund.1.currency)
+    }
+
+    /// Returns what is missing from the report for lack of exchange rates
+    fn notes(&self) -> Vec<String> {
+        let mut notes = Vec::new();
+        if let Err(ref e) = self.total {
+            notes.push(format!("Total unavailable: {}", e));
+        }
+        if !self.unconverted.is_empty() {
+            notes.push(format!(
+                "Left out of the charts for lack of an exchange rate to {}: {}",
+                self.currency,
+                self.unconverted.join(", ")
+            ));
+        }
+        notes
+    }
+
+    fn title(&self) -> String {
+        format!("FundWarrior report for {}", self.month.format("%B %Y"))
+    }
+}
+
+/// Returns a standalone HTML page showing the report
+pub fn html(data: &ReportData) -> String {
+    let mut page = String::new();
+    let _ = writeln!(page, "<!DOCTYPE html>");
+    let _ = writeln!(page, "<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">");
+    let _ = writeln!(page, "<title>{}</title>", escape(&data.title()));
+    let _ = writeln!(page, "<style>\n{}\n</style>\n</head>\n<body>", STYLESHEET);
+    let _ = writeln!(page, "<h1>{}</h1>", escape(&data.title()));
+
+    let _ = writeln!(page, "<h2>Funds</h2>\n<table>");
+    let _ = writeln!(
+        page,
+        "<tr><th>Name</th><th class=\"money\">Amount</th><th class=\"money\">Goal</th>\
+         <th class=\"money\">Remaining</th><th>Progress</th></tr>"
+    );
+    let total = data.total.as_ref().ok().map(|total| ("Total", total));
+    for &(name, fund) in data.funds.iter().chain(total.as_ref()) {
+        let _ = writeln!(
+            page,
+            "<tr><td>{}</td><td class=\"money {}\">{}</td><td class=\"money\">{}</td>\
+             <td class=\"money\">{}</td><td>{}</td></tr>",
+            escape(name),
+            status_class(fund),
+            escape(&data.money(i64::from(fund.amount), fund.currency)),
+            escape(&data.money(i64::from(fund.goal), fund.currency)),
+            escape(&data.money(fund.remaining(), fund.currency)),
+            progress_svg(fund)
+        );
+    }
+    let _ = writeln!(page, "</table>");
+    for note in data.notes() {
+        let _ = writeln!(page, "<p>{}</p>", escape(&note));
+    }
+
+    let _ = writeln!(page, "<h2>Spending this month</h2>");
+    let _ = writeln!(
+        page,
+        "{}",
+        svg_element(&pie_chart(data, 0), WIDTH, pie_height(data))
+    );
+    let _ = writeln!(page, "<h2>Balances over the last {} days</h2>", TREND_DAYS);
+    let _ = writeln!(
+        page,
+        "{}",
+        svg_element(&trend_chart(data, 0), WIDTH, trend_height(data))
+    );
+
+    let _ = writeln!(page, "<h2>Transactions</h2>\n<table>");
+    let _ = writeln!(
+        page,
+        "<tr><th>Date</th><th>Fund</th><th>Payee</th><th class=\"money\">Amount</th></tr>"
+    );
+    for transaction in &data.transactions {
+        for leg in &transaction.legs {
+            let _ = writeln!(
+                page,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td class=\"money\">{}</td></tr>",
+                transaction.date.format("%Y-%m-%d"),
+                escape(&leg.fund),
+                escape(transaction.payee.as_deref().unwrap_or_default()),
+                escape(&data.money(i64::from(leg.amount), data.currency_of(&leg.fund)))
+            );
+        }
+    }
+    if data.transactions.is_empty() {
+        let _ = writeln!(
+            page,
+            "<tr><td colspan=\"4\">No transactions this month</td></tr>"
+        );
+    }
+    let _ = writeln!(page, "</table>\n</body>\n</html>");
+    page
+}
+
+/// Returns a standalone SVG image showing the report
+pub fn svg(data: &ReportData) -> String {
+    let mut body = String::new();
+    let mut y = 40;
+    let _ = writeln!(
+        body,
+        "<text x=\"0\" y=\"24\" font-size=\"22\" font-weight=\"bold\">{}</text>",
+        escape(&data.title())
+    );
+
+    y = heading(&mut body, "Funds", y);
+    let total = data.total.as_ref().ok().map(|total| ("Total", total));
+    for &(name, fund) in data.funds.iter().chain(total.as_ref()) {
+        let _ = writeln!(
+            body,
+            "<text x=\"0\" y=\"{y}\">{}</text>\
+             <text x=\"330\" y=\"{y}\" text-anchor=\"end\" fill=\"{}\">{}</text>\
+             <text x=\"450\" y=\"{y}\" text-anchor=\"end\">{}</text>\
+             <g transform=\"translate(470 {})\">{}</g>",
+            escape(name),
+            status_color(fund),
+            escape(&data.money(i64::from(fund.amount), fund.currency)),
+            escape(&data.money(i64::from(fund.goal), fund.currency)),
+            y - 12,
+            progress_bar(fund),
+            y = y
+        );
+        y += 22;
+    }
+    for note in data.notes() {
+        let _ = writeln!(body, "<text x=\"0\" y=\"{}\">{}</text>", y, escape(&note));
+        y += 22;
+    }
+
+    y = heading(&mut body, "Spending this month", y + 10);
+    body.push_str(&pie_chart(data, y));
+    y += pie_height(data);
+
+    y = heading(
+        &mut body,
+        &format!("Balances over the last {} days", TREND_DAYS),
+        y + 10,
+    );
+    body.push_str(&trend_chart(data, y));
+    y += trend_height(data);
+
+    y = heading(&mut body, "Transactions", y + 10);
+    for transaction in &data.transactions {
+        for leg in &transaction.legs {
+            let _ = writeln!(
+                body,
+                "<text x=\"0\" y=\"{y}\">{}</text><text x=\"120\" y=\"{y}\">{}</text>\
+                 <text x=\"450\" y=\"{y}\" text-anchor=\"end\">{}</text>\
+                 <text x=\"470\" y=\"{y}\">{}</text>",
+                transaction.date.format("%Y-%m-%d"),
+                escape(&leg.fund),
+                escape(&data.money(i64::from(leg.amount), data.currency_of(&leg.fund))),
+                escape(transaction.payee.as_deref().unwrap_or_default()),
+                y = y
+            );
+            y += 20;
+        }
+    }
+    if data.transactions.is_empty() {
+        let _ = writeln!(
+            body,
+            "<text x=\"0\" y=\"{}\">No transactions this month</text>",
+            y
+        );
+        y += 20;
+    }
+
+    let mut image = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    image.push_str(&svg_element(
+        &format!(
+            "<rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n{}",
+            body
+        ),
+        WIDTH,
+        y,
+    ));
+    image
+}
+
+fn heading(body: &mut String, text: &str, y: i64) -> i64 {
+    let _ = writeln!(
+        body,
+        "<text x=\"0\" y=\"{}\" font-size=\"17\" font-weight=\"bold\">{}</text>",
+        y + 24,
+        escape(text)
+    );
+    y + 50
+}
+
+fn svg_element(content: &str, width: i64, height: i64) -> String {
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+         viewBox=\"0 0 {w} {h}\" font-family=\"sans-serif\" font-size=\"14\">\n{}</svg>",
+        content,
+        w = width,
+        h = height
+    )
+}
+
+fn progress_svg(fund: &Fund) -> String {
+    svg_element(&progress_bar(fund), 200, 16)
+}
+
+fn progress_bar(fund: &Fund) -> String {
+    let filled = (fund.progress().clamp(0.0, 1.0) * 160.0).round();
+    format!(
+        "<rect width=\"160\" height=\"14\" fill=\"#eee\"/>\
+         <rect width=\"{}\" height=\"14\" fill=\"{}\"/>\
+         <text x=\"166\" y=\"12\" font-size=\"12\">{:.0}%</text>",
+        filled,
+        status_color(fund),
+        fund.progress() * 100.0
+    )
+}
+
+fn pie_height(data: &ReportData) -> i64 {
+    (data.spending.len() as i64 * 22).max(220)
+}
+
+/// Returns a pie chart of the month's spending with a legend beside it,
+/// drawn `top` units from the top of the image
+fn pie_chart(data: &ReportData, top: i64) -> String {
+    let total: i64 = data.spending.iter().map(|entry| entry.1).sum();
+    let mut chart = String::new();
+    if total <= 0 {
+        let _ = writeln!(
+            chart,
+            "<text x=\"0\" y=\"{}\">Nothing spent this month</text>",
+            top + 20
+        );
+        return chart;
+    }
+    let (cx, cy, r) = (100.0, top as f64 + 100.0, 95.0);
+    let mut angle: f64 = 0.0;
+    for (index, &(ref name, spent)) in data.spending.iter().enumerate() {
+        let color = PALETTE[index % PALETTE.len()];
+        let fraction = spent as f64 / total as f64;
+        if fraction >= 1.0 {
+            let _ = writeln!(
+                chart,
+                "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"/>",
+                cx, cy, r, color
+            );
+        } else if fraction > 0.0 {
+            let end = angle + fraction * 2.0 * std::f64::consts::PI;
+            let _ = writeln!(
+                chart,
+                "<path d=\"M{:.2} {:.2} L{:.2} {:.2} A{} {} 0 {} 1 {:.2} {:.2} Z\" fill=\"{}\"/>",
+                cx,
+                cy,
+                cx + r * angle.sin(),
+                cy - r * angle.cos(),
+                r,
+                r,
+                if fraction > 0.5 { 1 } else { 0 },
+                cx + r * end.sin(),
+                cy - r * end.cos(),
+                color
+            );
+            angle = end;
+        }
+        let y = top + 16 + index as i64 * 22;
+        let _ = writeln!(
+            chart,
+            "<rect x=\"230\" y=\"{}\" width=\"14\" height=\"14\" fill=\"{}\"/>\
+             <text x=\"252\" y=\"{}\">{} — {} ({:.0}%)</text>",
+            y - 12,
+            color,
+            y,
+            escape(name),
+            escape(&data.money(spent, data.currency)),
+            fraction * 100.0
+        );
+    }
+    chart
+}
+
+fn trend_height(data: &ReportData) -> i64 {
+    240 + (data.trends.len() as i64 + 1) / 2 * 20
+}
+
+/// Returns a line per fund showing its balance over time, with a legend
+/// below, drawn `top` units from the top of the image
+fn trend_chart(data: &ReportData, top: i64) -> String {
+    let (left, width, height) = (90.0, (WIDTH - 100) as f64, 200.0);
+    let top_f = top as f64;
+    let values = data.trends.iter().flat_map(|trend| trend.1.iter().cloned());
+    let min = values.clone().min().unwrap_or(0).min(0);
+    let max = values.max().unwrap_or(0).max(min + 1);
+    let y_of = |value: i64| top_f + height - (value - min) as f64 / (max - min) as f64 * height;
+
+    let mut chart = String::new();
+    let _ = writeln!(
+        chart,
+        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"#ccc\"/>",
+        left, top_f, width, height
+    );
+    for &value in &[max, min] {
+        let _ = writeln!(
+            chart,
+            "<text x=\"{}\" y=\"{:.2}\" text-anchor=\"end\" font-size=\"12\">{}</text>",
+            left - 6.0,
+            y_of(value) + 4.0,
+            escape(&data.money(value, data.currency))
+        );
+    }
+    if min < 0 {
+        let _ = writeln!(
+            chart,
+            "<line x1=\"{}\" x2=\"{}\" y1=\"{y:.2}\" y2=\"{y:.2}\" stroke=\"#999\" stroke-dasharray=\"4 3\"/>",
+            left,
+            left + width,
+            y = y_of(0)
+        );
+    }
+    for (index, (name, balances)) in data.trends.iter().enumerate() {
+        let color = PALETTE[index % PALETTE.len()];
+        let step = width / (balances.len().max(2) - 1) as f64;
+        let points: Vec<String> = balances
+            .iter()
+            .enumerate()
+            .map(|(day, &balance)| format!("{:.2},{:.2}", left + day as f64 * step, y_of(balance)))
+            .collect();
+        let _ = writeln!(
+            chart,
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\"/>",
+            points.join(" "),
+            color
+        );
+        let legend_x = left + (index % 2) as f64 * width / 2.0;
+        let legend_y = top_f + height + 24.0 + (index / 2) as f64 * 20.0;
+        let _ = writeln!(
+            chart,
+            "<rect x=\"{}\" y=\"{}\" width=\"14\" height=\"4\" fill=\"{}\"/>\
+             <text x=\"{}\" y=\"{}\">{}</text>",
+            legend_x,
+            legend_y - 5.0,
+            color,
+            legend_x + 20.0,
+            legend_y,
+            escape(name)
+        );
+    }
+    let _ = writeln!(
+        chart,
+        "<text x=\"{}\" y=\"{}\" font-size=\"12\">{}</text>\
+         <text x=\"{}\" y=\"{}\" font-size=\"12\" text-anchor=\"end\">{}</text>",
+        left,
+        top_f + height + 14.0,
+        (data.today - Duration::days(TREND_DAYS - 1)).format("%Y-%m-%d"),
+        left + width,
+        top_f + height + 14.0,
+        data.today.format("%Y-%m-%d")
+    );
+    chart
+}
+
+/// Returns the stylesheet class of a fund's status, as colored by
+/// `report::status_color`
+fn status_class(fund: &Fund) -> &'static str {
+    match report::status_color(fund) {
+        Color::Red => "negative",
+        Color::Yellow => "below",
+        _ => "met",
+    }
+}
+
+/// Returns the color of a fund's status, matching its stylesheet class
+fn status_color(fund: &Fund) -> &'static str {
+    match report::status_color(fund) {
+        Color::Red => "#c62828",
+        Color::Yellow => "#b8860b",
+        _ => "#2e7d32",
+    }
+}
+
+/// Escapes text for use in HTML and SVG documents
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{html, svg, ReportData};
+    use chrono::NaiveDate;
+    use libfund::currency::{Currency, Rates};
+    use libfund::history::{History, Transaction};
+    use libfund::{Fund, FundManager};
+    use settings::Settings;
+    use term::Style;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 18).unwrap()
+    }
+
+    fn documents(funds: &FundManager, history: &History) -> Vec<String> {
+        let style = Style::new(&Settings::new()).unwrap();
+        let data = ReportData::new(
+            funds,
+            history,
+            &Rates::new(),
+            Currency::USD,
+            today(),
+            &style,
+        );
+        vec![html(&data), svg(&data)]
+    }
+
+    #[test]
+    fn escapes_funds_and_payees() {
+        let mut funds = FundManager::new();
+        let name = "<b>Tom & \"Jerry\"</b>";
+        funds
+            .add_fund(name, Fund::new().with_amount(1000).build())
+            .unwrap();
+        let mut history = History::new();
+        history.push(
+            Transaction::new(today())
+                .with_leg(name, -500)
+                .with_payee("<script>&\"")
+                .build(),
+        );
+        for document in documents(&funds, &history) {
+            assert!(!document.contains("<b>"));
+            assert!(!document.contains("<script>"));
+            assert!(document.contains("&lt;b&gt;Tom &amp; &quot;Jerry&quot;&lt;/b&gt;"));
+            assert!(document.contains("&lt;script&gt;&amp;&quot;"));
+        }
+    }
+
+    #[test]
+    fn draws_charts_without_history() {
+        let mut funds = FundManager::new();
+        let mut found = documents(&funds, &History::new());
+        funds.add_fund("grocery", Fund::new().build()).unwrap();
+        found.extend(documents(&funds, &History::new()));
+        for document in found {
+            assert!(document.contains("Nothing spent this month"));
+            assert!(document.contains("No transactions this month"));
+            assert!(!document.contains("NaN"));
+            assert!(!document.contains("inf"));
+        }
+    }
+}
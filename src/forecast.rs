@@ -0,0 +1,353 @@
+//! The `forecast` command, which projects balances from the scheduled
+//! transactions in the config file and from recent spending.
+//!
+//! A schedule is described by `schedule.<name>.*` settings:
+//!
+//! ```text
+//! schedule.rent.fund = rent
+//! schedule.rent.spend = 900.00
+//! schedule.rent.every = monthly
+//! schedule.rent.start = 2026-11-01
+//! ```
+//!
+//! `deposit` takes the place of `spend` for money coming in, and `every`
+//! defaults to `monthly`.
+
+use std::collections::BTreeMap;
+use std::io;
+
+use chrono::NaiveDate;
+use libfund::forecast::{FundForecast, Recurrence, Schedule};
+use libfund::{money, Fund, FundManager, NameMatching};
+
+use chart;
+use parse_date;
+use settings::Settings;
+use term;
+use term::{Color, Style};
+
+/// Reads the schedules in `settings`, matching their funds to `funds` as
+/// `matching` says
+///
+/// # Errors
+///
+/// * When a schedule is missing its fund, amount or start, or one of them
+///   is invalid
+pub fn schedules(
+    settings: &Settings,
+    funds: &FundManager,
+    matching: NameMatching,
+    today: NaiveDate,
+) -> Result<Vec<Schedule>, io::Error> {
+    let mut fields: BTreeMap<&str, BTreeMap<&str, &str>> = BTreeMap::new();
+    for (key, value) in settings.with_prefix("schedule.") {
+        match key.rsplit_once('.') {
+            Some((name, field)) => fields.entry(name).or_default().insert(field, value),
+            None => return Err(invalid(key, "expected schedule.<name>.<setting>")),
+        };
+    }
+
+    let mut schedules = Vec::new();
+    for (name, fields) in fields {
+        let field = |field: &str| {
+            fields
+                .get(field)
+                .cloned()
+                .ok_or_else(|| invalid(name, &format!("'{}' is missing", field)))
+        };
+        for key in fields.keys() {
+            if !["fund", "spend", "deposit", "every", "start"].contains(key) {
+                return Err(invalid(
+                    name,
+                    &format!(
+                        "unknown setting '{}', expected fund, spend, deposit, every or start",
+                        key
+                    ),
+                ));
+            }
+        }
+        let fund = funds
+            .resolve(field("fund")?, matching)
+            .map_err(|e| invalid(name, &e.to_string()))?;
+        let currency = funds
+            .fund(fund)
+            .map_err(|e| invalid(name, &e.to_string()))?
+            .currency;
+        let amount = |value: &str| match money::parse_amount(value, currency) {
+            Ok(amount) if amount >= 0 => Ok(amount),
+            Ok(_) => Err(invalid(name, &format!("'{}' is negative", value))),
+            Err(e) => Err(invalid(name, &e.to_string())),
+        };
+        let amount = match (fields.get("spend"), fields.get("deposit")) {
+            (Some(spend), None) => -amount(spend)?,
+            (None, Some(deposit)) => amount(deposit)?,
+            _ => return Err(invalid(name, "give either 'spend' or 'deposit'")),
+        };
+        let recurrence = fields
+            .get("every")
+            .map_or(Ok(Recurrence::Months(1)), |every| every.parse())
+            .map_err(|e| invalid(name, &e.to_string()))?;
+        let start = parse_date(field("start")?, today).map_err(|e| invalid(name, &e))?;
+        schedules.push(Schedule {
+            fund: String::from(fund),
+            amount,
+            recurrence,
+            start,
+        });
+    }
+    Ok(schedules)
+}
+
+fn invalid(name: &str, message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("schedule.{}: {}", name, message),
+    )
+}
+
+/// Prints each fund's balance today and at the end of the forecast, with
+/// the day it goes below zero in red and the day it reaches its goal in
+/// green
+pub fn print_forecasts(
+    funds: &FundManager,
+    forecasts: &[FundForecast],
+    until: NaiveDate,
+    style: &Style,
+) {
+    let until = until.format("%Y-%m-%d").to_string();
+    let name_width = forecasts
+        .iter()
+        .map(|forecast| term::width(&forecast.name))
+        .max()
+        .unwrap_or(0)
+        .max(4);
+    println!(
+        "{}",
+        term::paint(
+            &format!(
+                "{:<width$}  {:>12}  {:>12}  {:<10}  {}",
+                "Fund",
+                "Today",
+                until,
+                "Below zero",
+                "Goal",
+                width = name_width
+            ),
+            Color::Bold,
+            style.color
+        )
+    );
+    for forecast in forecasts {
+        let money = match funds.fund(&forecast.name) {
+            Ok(fund) => style.money_for(fund.currency),
+            Err(_) => continue,
+        };
+        let date = |date: Option<NaiveDate>, color| match date {
+            Some(date) => term::paint(&date.format("%Y-%m-%d").to_string(), color, style.color),
+            None => format!("{:<10}", "-"),
+        };
+        let line = format!(
+            "{:<width$}  {:>12}  {:>12}  {}  {}",
+            forecast.name,
+            money.format(forecast.balances.first().cloned().unwrap_or_default()),
+            money.format(forecast.last()),
+            date(forecast.negative_on, Color::Red),
+            date(forecast.goal_on, Color::Green),
+            width = name_width
+        );
+        println!("{}", line.trim_end());
+    }
+}
+
+/// Prints a chart of the projected balance of one fund, followed by the
+/// days something happens to it: a scheduled transaction, going below
+/// zero or reaching its goal
+pub fn print_fund_forecast(
+    fund: &Fund,
+    forecast: &FundForecast,
+    schedules: &[Schedule],
+    until: NaiveDate,
+    style: &Style,
+) {
+    let money = style.money_for(fund.currency);
+    let title = format!(
+        "Forecast of {} until {}",
+        forecast.name,
+        until.format("%Y-%m-%d")
+    );
+    chart::print_balance_chart(
+        &title,
+        &forecast.balances,
+        forecast.today,
+        until,
+        &money,
+        style,
+    );
+    println!();
+    if forecast.daily_spending > 0.0 {
+        let daily = money.format(forecast.daily_spending.round() as i64);
+        println!(
+            "Expecting {} of spending a day besides what is scheduled",
+            daily
+        );
+    }
+
+    let mut events: BTreeMap<NaiveDate, Vec<(String, Color)>> = BTreeMap::new();
+    for schedule in schedules
+        .iter()
+        .filter(|schedule| schedule.fund == forecast.name)
+    {
+        for date in schedule.dates(forecast.today, until) {
+            let (verb, color) = if schedule.amount < 0 {
+                ("spend", Color::Yellow)
+            } else {
+                ("deposit", Color::Green)
+            };
+            let event = format!(
+                "{} {}",
+                verb,
+                money.format(i64::from(schedule.amount.abs()))
+            );
+            events.entry(date).or_default().push((event, color));
+        }
+    }
+    if let Some(date) = forecast.negative_on {
+        events
+            .entry(date)
+            .or_default()
+            .push((String::from("below zero"), Color::Red));
+    }
+    if let Some(date) = forecast.goal_on {
+        events
+            .entry(date)
+            .or_default()
+            .push((String::from("reaches goal"), Color::Green));
+    }
+    for (date, events) in events {
+        let balance = money.format(forecast.balance_on(date).unwrap_or_default());
+        let events: Vec<String> = events
+            .iter()
+            .map(|(event, color)| term::paint(event, *color, style.color))
+            .collect();
+        println!(
+            "{}  {:>12}  {}",
+            date.format("%Y-%m-%d"),
+            balance,
+            events.join(", ")
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::schedules;
+    use chrono::NaiveDate;
+    use libfund::forecast::{Recurrence, Schedule};
+    use libfund::{Fund, FundManager, NameMatching};
+    use settings::Settings;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 18).unwrap()
+    }
+
+    fn funds() -> FundManager {
+        let mut funds = FundManager::new();
+        funds.add_fund("rent", Fund::new().build()).unwrap();
+        funds.add_fund("grocery", Fund::new().build()).unwrap();
+        funds
+    }
+
+    fn error(pairs: &[(&str, &str)]) -> String {
+        let mut settings = Settings::new();
+        for &(key, value) in pairs {
+            settings.set(key, value);
+        }
+        schedules(&settings, &funds(), NameMatching::Loose, today())
+            .unwrap_err()
+            .to_string()
+    }
+
+    #[test]
+    fn reads_schedules() {
+        let mut settings = Settings::new();
+        settings.set("schedule.rent.fund", "rent");
+        settings.set("schedule.rent.spend", "900.00");
+        settings.set("schedule.rent.start", "2026-11-01");
+        settings.set("schedule.pay.fund", "gro");
+        settings.set("schedule.pay.deposit", "50");
+        settings.set("schedule.pay.every", "biweekly");
+        settings.set("schedule.pay.start", "-2d");
+
+        let found = schedules(&settings, &funds(), NameMatching::Loose, today()).unwrap();
+        assert_eq!(
+            found,
+            vec![
+                Schedule {
+                    fund: String::from("grocery"),
+                    amount: 5000,
+                    recurrence: Recurrence::Days(14),
+                    start: NaiveDate::from_ymd_opt(2026, 10, 16).unwrap(),
+                },
+                Schedule {
+                    fund: String::from("rent"),
+                    amount: -90000,
+                    recurrence: Recurrence::Months(1),
+                    start: NaiveDate::from_ymd_opt(2026, 11, 1).unwrap(),
+                },
+            ]
+        );
+        assert!(schedules(&settings, &funds(), NameMatching::Exact, today()).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_schedules() {
+        let rent = [
+            ("schedule.rent.fund", "rent"),
+            ("schedule.rent.start", "2026-11-01"),
+        ];
+        let with = |extra: &[(&'static str, &'static str)]| {
+            let mut pairs = rent.to_vec();
+            pairs.extend_from_slice(extra);
+            error(&pairs)
+        };
+        assert_eq!(with(&[]), "schedule.rent: give either 'spend' or 'deposit'");
+        assert_eq!(
+            with(&[
+                ("schedule.rent.spend", "1.00"),
+                ("schedule.rent.deposit", "1.00")
+            ]),
+            "schedule.rent: give either 'spend' or 'deposit'"
+        );
+        assert_eq!(
+            with(&[("schedule.rent.spend", "lots")]),
+            "schedule.rent: 'lots' is not an amount, expected a number such as 12.50"
+        );
+        assert!(with(&[
+            ("schedule.rent.spend", "1.00"),
+            ("schedule.rent.every", "hourly")
+        ])
+        .starts_with("schedule.rent: "));
+        assert!(with(&[
+            ("schedule.rent.spend", "1.00"),
+            ("schedule.rent.amount", "1.00")
+        ])
+        .starts_with("schedule.rent: unknown setting 'amount'"));
+        assert_eq!(
+            error(&[
+                ("schedule.car.spend", "1.00"),
+                ("schedule.car.start", "today")
+            ]),
+            "schedule.car: 'fund' is missing"
+        );
+        assert!(error(&[
+            ("schedule.car.fund", "car"),
+            ("schedule.car.spend", "1.00"),
+            ("schedule.car.start", "today")
+        ])
+        .starts_with("schedule.car: fund 'car' not found"));
+        assert_eq!(
+            error(&[("schedule.rent", "rent")]),
+            "schedule.rent: expected schedule.<name>.<setting>"
+        );
+    }
+}
@@ -0,0 +1,247 @@
+//! Hook scripts, run when funds change, in the `hooks` directory of the
+//! config directory. Every executable named after an event, or after an
+//! event followed by `-` or `.` such as `on-spend-notify.sh`, runs for that
+//! event in order of their names. Editor backups ending in `~` or `.bak`
+//! are skipped. The events are:
+//!
+//! * `on-spend` and `on-deposit` run before spending or depositing is saved
+//! * `on-modify` runs before any change is saved
+//! * `on-exit` runs after a command has finished and its changes are saved
+//!
+//! Each hook is given a JSON object on standard input holding the
+//! `event`, the `command`, the `profile`, whether it is a `dry_run`, and
+//! the data `before` and `after` the command, in the format written by
+//! `fund export json`. Anything the hook prints is shown to the user. A
+//! hook that exits with a non-zero status vetoes the change, except for
+//! `on-exit`, which runs once it is too late to veto anything.
+//!
+//! On a dry run the hooks run before a change as usual, with `dry_run`
+//! set, so they can warn about or veto what would change. `on-exit` doesn't
+//! run, as nothing is saved.
+
+use std::fs;
+use std::io;
+use std::io::prelude::*;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+
+use libfund::backup::Backup;
+use serde_json::{self, Value};
+
+use {Config, Data};
+
+/// Runs the hooks for the change `command` makes from `before` to
+/// `after`, returning what they printed. Nothing runs when nothing
+/// changed.
+///
+/// # Errors
+///
+/// * When a hook could not be run
+/// * When a hook vetoes the change by exiting with a non-zero status
+pub fn check(
+    config: &Config,
+    command: &str,
+    before: &Data,
+    after: &Data,
+) -> Result<String, io::Error> {
+    if before == after {
+        return Ok(String::new());
+    }
+    let mut events = Vec::new();
+    match command {
+        "spend" => events.push("on-spend"),
+        "deposit" => events.push("on-deposit"),
+        _ => {}
+    }
+    events.push("on-modify");
+
+    let mut output = String::new();
+    for event in events {
+        output.push_str(&run_event(
+            config,
+            event,
+            command,
+            before,
+            after,
+            "rejected the change",
+        )?);
+    }
+    Ok(output)
+}
+
+/// Runs the `on-exit` hooks once `command` has finished, unless this is
+/// a dry run, and returns what they printed
+///
+/// # Errors
+///
+/// * When a hook could not be run or exits with a non-zero status. The
+///   changes are saved regardless.
+pub fn exit(
+    config: &Config,
+    command: &str,
+    before: &Data,
+    after: &Data,
+) -> Result<String, io::Error> {
+    if config.dry_run {
+        return Ok(String::new());
+    }
+    run_event(config, "on-exit", command, before, after, "failed")
+}
+
+fn run_event(
+    config: &Config,
+    event: &str,
+    command: &str,
+    before: &Data,
+    after: &Data,
+    failure: &str,
+) -> Result<String, io::Error> {
+    let scripts = scripts(&config.configdir.join("hooks"), event)?;
+    if scripts.is_empty() {
+        return Ok(String::new());
+    }
+    let mut input = serde_json::to_vec(&serde_json::json!({
+        "event": event,
+        "command": command,
+        "profile": config.profile,
+        "dry_run": config.dry_run,
+        "before": to_json(before)?,
+        "after": to_json(after)?,
+    }))?;
+    input.push(b'\n');
+
+    let mut output = String::new();
+    for script in scripts {
+        let mut child = Command::new(&script)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| io::Error::new(e.kind(), format!("can't run hook {:?}: {}", script, e)))?;
+        // Write from another thread, so a hook that prints while reading
+        // can't fill its output pipe and wait on us forever
+        let writer = child.stdin.take().map(|mut stdin| {
+            let input = input.clone();
+            thread::spawn(move || stdin.write_all(&input))
+        });
+        let result = child.wait_with_output()?;
+        if let Some(writer) = writer {
+            match writer.join() {
+                // The hook is free to exit without reading its input
+                Ok(Err(ref e)) if e.kind() == io::ErrorKind::BrokenPipe => {}
+                Ok(result) => result?,
+                Err(_) => return Err(io::Error::other("can't write to hook")),
+            }
+        }
+        let mut printed = String::from_utf8_lossy(&result.stdout).into_owned();
+        printed.push_str(&String::from_utf8_lossy(&result.stderr));
+        if !result.status.success() {
+            let name = script.file_name().unwrap_or_default().to_string_lossy();
+            let message = match printed.trim() {
+                "" => format!("hook {} {}", name, failure),
+                reason => format!("hook {} {}: {}", name, failure, reason),
+            };
+            return Err(io::Error::other(message));
+        }
+        output.push_str(&printed);
+    }
+    Ok(output)
+}
+
+/// Returns the hooks for `event` in `dir`, sorted by name
+fn scripts(dir: &Path, event: &str) -> Result<Vec<PathBuf>, io::Error> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut scripts = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        if is_hook_for(&entry.file_name().to_string_lossy(), event)
+            && is_executable(&entry.metadata()?)
+        {
+            scripts.push(entry.path());
+        }
+    }
+    scripts.sort();
+    Ok(scripts)
+}
+
+/// Whether a file called `name` is a hook for `event`: `event` itself or
+/// `event` followed by `-` or `.`, but not an editor backup
+fn is_hook_for(name: &str, event: &str) -> bool {
+    let rest = match name.strip_prefix(event) {
+        Some(rest) => rest,
+        None => return false,
+    };
+    (rest.is_empty() || rest.starts_with(['-', '.']))
+        && !name.ends_with('~')
+        && !name.ends_with(".bak")
+}
+
+#[cfg(unix)]
+fn is_executable(metadata: &fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(metadata: &fs::Metadata) -> bool {
+    metadata.is_file()
+}
+
+fn to_json(data: &Data) -> Result<Value, io::Error> {
+    let backup = Backup::new(data.funds.clone(), data.history.clone(), data.rates.clone());
+    Ok(serde_json::from_str(&backup.to_json())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_hook_for, scripts};
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn matches_hooks_by_event() {
+        for name in &["on-spend", "on-spend-notify.sh", "on-spend.py"] {
+            assert!(is_hook_for(name, "on-spend"), "{} skipped", name);
+        }
+        for name in &[
+            "on-spending",
+            "on-spend~",
+            "on-spend.bak",
+            "on-spend-notify.sh~",
+            "on-deposit",
+            "old-on-spend",
+        ] {
+            assert!(!is_hook_for(name, "on-spend"), "{} matched", name);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn skips_files_that_are_not_executable() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = env::temp_dir().join("fund-hooks-test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        for (name, mode) in &[
+            ("on-exit-b", 0o755),
+            ("on-exit-a", 0o755),
+            ("on-exit", 0o644),
+        ] {
+            let path = dir.join(name);
+            fs::write(&path, "#!/bin/sh\n").unwrap();
+            fs::set_permissions(&path, fs::Permissions::from_mode(*mode)).unwrap();
+        }
+        assert_eq!(
+            scripts(&dir, "on-exit").unwrap(),
+            vec![dir.join("on-exit-a"), dir.join("on-exit-b")]
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,367 @@
+use std::error::Error;
+use std::io;
+use std::io::prelude::*;
+use std::io::IsTerminal;
+use std::path::Path;
+
+use libfund::history::History;
+use libfund::import::{self, ColumnRef, CsvProfile, Rules, StatementEntry};
+use libfund::{FundManager, FundManagerError};
+
+use settings::Settings;
+use term::Style;
+
+/// How many entries of an import ended up where
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub struct ImportSummary {
+    pub imported: usize,
+    /// Entries skipped because an earlier import already recorded them
+    pub duplicates: usize,
+    /// Entries that were not assigned to any fund
+    pub unassigned: usize,
+}
+
+/// Reads the CSV profile for `bank` from `import/profiles/<bank>` in the
+/// config directory. Profiles use the same `key = value` format as
+/// `fundrc`.
+///
+/// # Errors
+///
+/// * When the profile does not exist or could not be read
+/// * When a setting has an invalid value
+pub fn load_csv_profile(
+    configdir: &Path,
+    bank: &str,
+) -> Result<CsvProfile, Box<dyn Error + Send + Sync>> {
+    let path = configdir.join("import").join("profiles").join(bank);
+    if !path.is_file() {
+        return Err(From::from(format!("no import profile at {:?}", path)));
+    }
+    let settings = Settings::load(&path)?;
+    let mut profile = CsvProfile::new();
+    if let Some(delimiter) = settings.get("delimiter") {
+        profile.delimiter = match delimiter {
+            "tab" => '\t',
+            "semicolon" => ';',
+            "comma" => ',',
+            _ => single_char(delimiter, "delimiter")?,
+        };
+    }
+    if let Some(skip) = settings.get("skip") {
+        profile.skip = skip.parse()?;
+    }
+    if let Some(header) = settings.get("header") {
+        profile.header = settings
+            .get_bool("header")
+            .ok_or_else(|| format!("header should be yes or no, not '{}'", header))?;
+    }
+    if let Some(date) = settings.get("date") {
+        profile.date = ColumnRef::from(date);
+    }
+    if let Some(date_format) = settings.get("date_format") {
+        profile.date_format = String::from(date_format);
+    }
+    if let Some(amount) = settings.get("amount") {
+        profile.amount = Some(ColumnRef::from(amount));
+    }
+    profile.debit = settings.get("debit").map(ColumnRef::from);
+    profile.credit = settings.get("credit").map(ColumnRef::from);
+    if let Some(description) = settings.get("description") {
+        profile.description = ColumnRef::from(description);
+    }
+    profile.id = settings.get("id").map(ColumnRef::from);
+    if let Some(sign) = settings.get("sign") {
+        profile.inverted = match sign {
+            "normal" => false,
+            "inverted" => true,
+            _ => {
+                return Err(From::from(format!(
+                    "sign should be normal or inverted, not '{}'",
+                    sign
+                )))
+            }
+        };
+    }
+    if let Some(decimal) = settings.get("decimal") {
+        profile.decimal_separator = single_char(decimal, "decimal")?;
+    }
+    if let Some(decimals) = settings.get("decimals") {
+        profile.decimals = decimals.parse()?;
+    }
+    Ok(profile)
+}
+
+fn single_char(value: &str, key: &str) -> Result<char, Box<dyn Error + Send + Sync>> {
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(From::from(format!(
+            "{} should be a single character, not '{}'",
+            key, value
+        ))),
+    }
+}
+
+/// Posts statement entries to the funds chosen by `rules`. Entries no
+/// rule matches are prompted for when stdin is a terminal, and left
+/// unassigned otherwise. Entries recorded by an earlier import are
+/// skipped.
+///
+/// # Errors
+///
+/// * When a rule names a fund that does not exist
+/// * When reading the answer to a prompt fails
+pub fn import_entries(
+    entries: &[StatementEntry],
+    rules: &Rules,
+    funds: &mut FundManager,
+    history: &mut History,
+    style: &Style,
+) -> Result<ImportSummary, FundManagerError> {
+    let stdin = io::stdin();
+    let mut input = if stdin.is_terminal() {
+        Some(stdin.lock())
+    } else {
+        None
+    };
+    import_from(
+        entries,
+        rules,
+        funds,
+        history,
+        style,
+        input.as_mut().map(|input| input as &mut dyn BufRead),
+    )
+}
+
+/// Posts statement entries like `import_entries`, prompting for entries
+/// no rule matches on `input` if there is one
+fn import_from(
+    entries: &[StatementEntry],
+    rules: &Rules,
+    funds: &mut FundManager,
+    history: &mut History,
+    style: &Style,
+    mut input: Option<&mut dyn BufRead>,
+) -> Result<ImportSummary, FundManagerError> {
+    let mut summary = ImportSummary::default();
+    for entry in entries {
+        if history.contains_import(&entry.id) {
+            summary.duplicates += 1;
+            continue;
+        }
+        let fund = match rules.fund_for(&entry.description) {
+            Some(fund) => Some(String::from(fund)),
+            None => match input {
+                Some(ref mut reader) => match prompt_for_fund(entry, funds, style, *reader)? {
+                    Answer::Fund(fund) => Some(fund),
+                    Answer::Skip => None,
+                    Answer::Quit => {
+                        input = None;
+                        None
+                    }
+                },
+                None => None,
+            },
+        };
+        match fund {
+            Some(fund) => {
+                import::record(entry, &fund, funds, history)?;
+                summary.imported += 1;
+            }
+            None => summary.unassigned += 1,
+        }
+    }
+    Ok(summary)
+}
+
+enum Answer {
+    Fund(String),
+    Skip,
+    Quit,
+}
+
+/// Asks which fund an entry belongs to until the name of an existing fund,
+/// a blank line to skip the entry or `q` to skip all remaining entries is
+/// read from `input`. A fund called `q` is chosen rather than stopping, so
+/// then only the end of input stops.
+fn prompt_for_fund(
+    entry: &StatementEntry,
+    funds: &FundManager,
+    style: &Style,
+    input: &mut dyn BufRead,
+) -> Result<Answer, io::Error> {
+    let names: Vec<&str> = funds.into_iter().map(|(name, _)| name.as_str()).collect();
+    println!(
+        "{}  {}  {}",
+        entry.date,
+        entry.description,
+        style.money.format(i64::from(entry.amount))
+    );
+    let stop = if funds.fund("q").is_ok() {
+        "Ctrl-D"
+    } else {
+        "q"
+    };
+    loop {
+        print!(
+            "Fund ({}; blank to skip, {} to stop asking): ",
+            names.join(", "),
+            stop
+        );
+        io::stdout().flush()?;
+        let mut answer = String::new();
+        if input.read_line(&mut answer)? == 0 {
+            return Ok(Answer::Quit);
+        }
+        match answer.trim() {
+            "" => return Ok(Answer::Skip),
+            name if funds.fund(name).is_ok() => return Ok(Answer::Fund(String::from(name))),
+            "q" => return Ok(Answer::Quit),
+            name => println!("There is no fund called '{}'", name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{import_from, load_csv_profile, ImportSummary};
+    use chrono::NaiveDate;
+    use libfund::history::History;
+    use libfund::import::{ColumnRef, Rules, StatementEntry};
+    use libfund::{Fund, FundManager};
+    use settings::Settings;
+    use std::env;
+    use std::fs;
+    use std::io::BufRead;
+    use std::path::Path;
+    use term::Style;
+
+    fn write_profile(configdir: &Path, text: &str) {
+        let dir = configdir.join("import").join("profiles");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("bank"), text).unwrap();
+    }
+
+    #[test]
+    fn loads_csv_profiles() {
+        let configdir = env::temp_dir().join("fund-import-profile-test");
+        let _ = fs::remove_dir_all(&configdir);
+        assert!(load_csv_profile(&configdir, "bank").is_err());
+
+        write_profile(
+            &configdir,
+            "delimiter = semicolon\nheader = no\ndate = 2\namount = Betrag\n\
+             sign = inverted\ndecimal = ,\ndecimals = 0\n",
+        );
+        let profile = load_csv_profile(&configdir, "bank").unwrap();
+        assert_eq!(profile.delimiter, ';');
+        assert!(!profile.header);
+        assert_eq!(profile.date, ColumnRef::Index(1));
+        assert_eq!(profile.amount, Some(ColumnRef::from("Betrag")));
+        assert!(profile.inverted);
+        assert_eq!(profile.decimal_separator, ',');
+        assert_eq!(profile.decimals, 0);
+
+        write_profile(&configdir, "delimiter = tab\n");
+        assert_eq!(
+            load_csv_profile(&configdir, "bank").unwrap().delimiter,
+            '\t'
+        );
+        write_profile(&configdir, "delimiter = |\n");
+        assert_eq!(load_csv_profile(&configdir, "bank").unwrap().delimiter, '|');
+
+        for invalid in &[
+            "delimiter = pipe",
+            "decimal = ,,",
+            "sign = backwards",
+            "header = maybe",
+            "skip = -1",
+            "decimals = two",
+        ] {
+            write_profile(&configdir, invalid);
+            assert!(
+                load_csv_profile(&configdir, "bank").is_err(),
+                "{} loaded",
+                invalid
+            );
+        }
+        fs::remove_dir_all(&configdir).unwrap();
+    }
+
+    fn entry(description: &str, amount: i32, id: &str) -> StatementEntry {
+        StatementEntry {
+            date: NaiveDate::from_ymd_opt(2026, 10, 18).unwrap(),
+            amount,
+            description: String::from(description),
+            id: String::from(id),
+        }
+    }
+
+    fn import(
+        entries: &[StatementEntry],
+        funds: &mut FundManager,
+        history: &mut History,
+        input: Option<&mut dyn BufRead>,
+    ) -> ImportSummary {
+        let mut rules = Rules::new();
+        rules.add("grocery", "kroger").unwrap();
+        let style = Style::new(&Settings::new()).unwrap();
+        import_from(entries, &rules, funds, history, &style, input).unwrap()
+    }
+
+    #[test]
+    fn imports_entries_without_asking() {
+        let mut funds = FundManager::new();
+        funds.add_fund("grocery", Fund::new().build()).unwrap();
+        let mut history = History::new();
+        let entries = [
+            entry("KROGER #12", -2500, "1"),
+            entry("Shell Oil", -4000, "2"),
+            entry("Kroger", -1000, "3"),
+            entry("KROGER #12", -2500, "1"),
+        ];
+
+        let summary = import(&entries, &mut funds, &mut history, None);
+        assert_eq!(
+            summary,
+            ImportSummary {
+                imported: 2,
+                duplicates: 1,
+                unassigned: 1,
+            }
+        );
+        assert_eq!(funds.fund("grocery").unwrap().amount, -3500);
+
+        let summary = import(&entries, &mut funds, &mut history, None);
+        assert_eq!(summary.duplicates, 3);
+        assert_eq!(summary.unassigned, 1);
+        assert_eq!(history.len(), 2);
+    }
+
+    #[test]
+    fn asks_for_unmatched_entries() {
+        let mut funds = FundManager::new();
+        funds.add_fund("grocery", Fund::new().build()).unwrap();
+        funds.add_fund("q", Fund::new().build()).unwrap();
+        let mut history = History::new();
+        let entries = [
+            entry("Shell Oil", -4000, "1"),
+            entry("Cinema", -1500, "2"),
+            entry("Bookshop", -800, "3"),
+        ];
+
+        let mut answers = "nothing\nq\n\n".as_bytes();
+        let summary = import(&entries, &mut funds, &mut history, Some(&mut answers));
+        assert_eq!(summary.imported, 1);
+        assert_eq!(summary.unassigned, 2);
+        assert_eq!(funds.fund("q").unwrap().amount, -4000);
+
+        funds.rename("q", "fun").unwrap();
+        let entries = [entry("Cinema", -1500, "4"), entry("Bookshop", -800, "5")];
+        let mut answers = "q\nfun\n".as_bytes();
+        let summary = import(&entries, &mut funds, &mut history, Some(&mut answers));
+        assert_eq!(summary.imported, 0);
+        assert_eq!(summary.unassigned, 2);
+    }
+}
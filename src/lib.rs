@@ -1,42 +1,176 @@
+extern crate chrono;
 extern crate clap;
 extern crate dirs;
 extern crate libfund;
+extern crate ratatui;
+extern crate rustyline;
+extern crate serde_json;
+extern crate terminal_size;
 
+pub mod batch;
+pub mod chart;
+pub mod cli;
+pub mod completions;
+pub mod document;
+pub mod forecast;
+pub mod hooks;
+pub mod import;
+pub mod profile;
+pub mod report;
+pub mod settings;
+pub mod shell;
+pub mod term;
+pub mod tui;
+
+use std::collections::BTreeMap;
 use std::error::Error;
+use std::fs;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use chrono::{Datelike, Duration, Local, NaiveDate};
 use clap::ArgMatches;
+use libfund::alerts::{Alert, Threshold};
+use libfund::backup::{Backup, MergeSummary};
+use libfund::currency::{Currency, Rates};
+use libfund::export::JournalFormat;
+use libfund::history::{History, Transaction};
+use libfund::import::{CsvProfile, Rules};
+use libfund::money::{Amount, MoneyFormat};
+use libfund::stats::{FundStats, Period};
+use libfund::{MergeStrategy, NameMatching};
+
+use document::ReportData;
+use import::ImportSummary;
+use profile::Profiles;
+use report::Report;
+use settings::Settings;
+use term::{Color, Style};
+
+/// The account funds are placed below when exporting to plain-text
+/// accounting formats, unless the `export.account` setting is given
+const DEFAULT_EXPORT_ACCOUNT: &str = "Assets:Checking:Funds";
+
+/// The most days of balances `graph` charts, about ten years
+const MAX_GRAPH_DAYS: i64 = 3660;
 
 pub struct Config {
     pub configdir: PathBuf,
     pub fundfile: PathBuf,
+    pub historyfile: PathBuf,
+    pub ratesfile: PathBuf,
+    pub settings: Settings,
+    pub report: Report,
+    pub style: Style,
     pub command: String,
     pub fund_name: Option<String>,
     pub transfer_name: Option<String>,
     pub field: Option<String>,
-    pub amount: Option<i32>,
-    pub goal: Option<i32>,
+    /// The amount given on the command line, in the currency of the fund
+    /// it is for
+    pub amount: Option<Amount>,
+    pub goal: Option<Amount>,
+    pub currency: Option<Currency>,
+    pub to_currency: Option<Currency>,
+    pub rate: Option<f64>,
+    pub period: Period,
+    pub days: i64,
+    pub html: Option<PathBuf>,
+    pub svg: Option<PathBuf>,
+    pub import_file: Option<PathBuf>,
+    pub csv_profile: Option<CsvProfile>,
+    pub day_first: bool,
+    pub journal_format: Option<JournalFormat>,
+    pub output: Option<PathBuf>,
+    pub merge: Option<MergeStrategy>,
+    pub replace: bool,
+    pub profiles: Profiles,
+    pub profile: String,
+    pub profile_name: Option<String>,
+    /// The file `batch` reads commands from, or `-` for standard input
+    pub batch_file: Option<PathBuf>,
+    pub continue_on_error: bool,
+    /// Shows what a command would change instead of saving it
+    pub dry_run: bool,
+    pub memo: Option<String>,
+    pub payee: Option<String>,
+    /// The date to record a transaction on, if not today
+    pub date: Option<NaiveDate>,
+    /// The funds and amounts a split `spend` takes from, each amount in
+    /// the currency of its fund
+    pub split: Vec<(String, Amount)>,
+    /// The shell `completions` writes a script for
+    pub completion_shell: Option<String>,
+    /// What `_names` lists: `funds`, `fields` or `profiles`
+    pub names: Option<String>,
+    /// The last day `forecast` projects balances for
+    pub until: Option<NaiveDate>,
+}
+
+/// Returns the config and data directories of `fund`
+fn directories() -> Result<(PathBuf, PathBuf), Box<dyn Error + Send + Sync>> {
+    let configdir = match dirs::config_dir() {
+        Some(mut path) => {
+            path.push(PathBuf::from(r"fund"));
+            path
+        }
+        None => return Err(From::from("can't find config directory")),
+    };
+    let datadir = match dirs::data_dir() {
+        Some(data_dir) => data_dir.join("fund"),
+        None => return Err(From::from("can't use this directory")),
+    };
+    Ok((configdir, datadir))
+}
+
+/// Reads the main `fundrc`, overlaid with the settings of `profile`
+fn profile_settings(
+    configdir: &Path,
+    profiles: &Profiles,
+    profile: &str,
+) -> Result<Settings, io::Error> {
+    let mut settings = Settings::load(&configdir.join("fundrc"))?;
+    if let Some(path) = profiles.settings_file(profile) {
+        settings.overlay(Settings::load(&path)?);
+    }
+    Ok(settings)
+}
+
+/// Reads the settings `Config::new` would use for `profile`, or for the
+/// profile in use when it is `None`, before the command line is parsed
+///
+/// # Errors
+///
+/// * When the directories can't be found or a settings file can't be read
+pub fn load_settings(profile: Option<&str>) -> Result<Settings, Box<dyn Error + Send + Sync>> {
+    let (configdir, datadir) = directories()?;
+    let profiles = Profiles::new(&configdir, &datadir);
+    let profile = match profile {
+        Some(name) => String::from(name),
+        None => profiles.current()?,
+    };
+    Ok(profile_settings(&configdir, &profiles, &profile)?)
 }
 
 impl Config {
-    pub fn new(matches: &ArgMatches) -> Result<Config, Box<Error + Send + Sync>> {
-        let configdir = match dirs::config_dir() {
-            Some(mut path) => {
-                path.push(PathBuf::from(r"fund"));
-                path
-            }
-            None => return Err(From::from("can't find config directory")),
-        };
-        let mut fundfile = match dirs::data_dir() {
-            Some(data_dir) => data_dir,
-            None => return Err(From::from("can't use this directory")),
+    pub fn new(matches: &ArgMatches) -> Result<Config, Box<dyn Error + Send + Sync>> {
+        let (configdir, datadir) = directories()?;
+        let ratesfile = datadir.join("rates");
+        let profiles = Profiles::new(&configdir, &datadir);
+        let profile = match matches.value_of("profile") {
+            Some(name) => String::from(name),
+            None => profiles.current()?,
         };
-        fundfile.push("fund/fund");
-        
+        if matches.subcommand_name() != Some("profile") {
+            profiles.check_exists(&profile)?;
+        }
+
+        let settings = profile_settings(&configdir, &profiles, &profile)?;
+        let mut fundfile = profiles.fundfile(&profile);
         if let Some(path) = matches.value_of("fundfile") {
             fundfile = PathBuf::from(path);
         }
+        let historyfile = fundfile.with_extension("history");
 
         let mut command = String::from(matches.subcommand().0);
         let mut fund_name = None;
@@ -44,12 +178,35 @@ impl Config {
         let mut goal = None;
         let mut transfer_name = None;
         let mut field = None;
+        let mut currency = None;
+        let mut to_currency = None;
+        let mut rate = None;
+        let mut period = None;
+        let mut days = None;
+        let mut html = None;
+        let mut svg = None;
+        let mut import_file = None;
+        let mut csv_profile = None;
+        let mut day_first = false;
+        let mut journal_format = None;
+        let mut output = None;
+        let mut merge = None;
+        let mut replace = false;
+        let mut batch_file = None;
+        let mut split = Vec::new();
+        let mut continue_on_error = false;
+        let mut completion_shell = None;
+        let mut names = None;
+        let mut until = None;
+        let mut profile_name = None;
+        let mut report = Report::default();
 
         match matches.subcommand() {
             ("new", Some(new_matches)) => {
                 fund_name = new_matches.value_of("name");
                 amount = new_matches.value_of("amount");
                 goal = new_matches.value_of("goal");
+                currency = new_matches.value_of("currency");
             }
             ("deposit", Some(deposit_matches)) => {
                 fund_name = deposit_matches.value_of("name");
@@ -58,9 +215,25 @@ impl Config {
             ("spend", Some(spend_matches)) => {
                 fund_name = spend_matches.value_of("name");
                 amount = spend_matches.value_of("amount");
+                if let Some(parts) = spend_matches.values_of("split") {
+                    split = parts.collect();
+                }
             }
             ("info", Some(list_matches)) => {
                 fund_name = list_matches.value_of("name");
+                currency = list_matches.value_of("currency");
+                if let Some(name) = list_matches.value_of("report") {
+                    report = Report::from_settings(&settings, name)?;
+                }
+                if let Some(columns) = list_matches.value_of("columns") {
+                    report.columns = report::parse_columns(columns)?;
+                }
+                if let Some(sort) = list_matches.value_of("sort") {
+                    report.sort = sort.parse()?;
+                }
+                if list_matches.is_present("reverse") {
+                    report.reverse = !report.reverse;
+                }
             }
             ("transfer", Some(list_matches)) => {
                 fund_name = list_matches.value_of("from_name");
@@ -76,49 +249,449 @@ impl Config {
                 amount = list_matches.value_of("amount");
                 field = list_matches.value_of("field");
             }
+            ("stats", Some(stats_matches)) => {
+                fund_name = stats_matches.value_of("name");
+                period = stats_matches.value_of("period");
+            }
+            ("forecast", Some(forecast_matches)) => {
+                fund_name = forecast_matches.value_of("name");
+                until = forecast_matches.value_of("until");
+            }
+            ("graph", Some(graph_matches)) => {
+                fund_name = graph_matches.value_of("name");
+                days = graph_matches.value_of("days");
+            }
+            ("report", Some(report_matches)) => {
+                currency = report_matches.value_of("currency");
+                html = report_matches.value_of("html").map(PathBuf::from);
+                svg = report_matches.value_of("svg").map(PathBuf::from);
+            }
+            ("import", Some(import_matches)) => match import_matches.subcommand() {
+                ("csv", Some(csv_matches)) => {
+                    command = String::from("import csv");
+                    import_file = csv_matches.value_of("file").map(PathBuf::from);
+                    if let Some(bank) = csv_matches.value_of("profile") {
+                        csv_profile = Some(import::load_csv_profile(&configdir, bank)?);
+                    }
+                }
+                ("ofx", Some(ofx_matches)) => {
+                    command = String::from("import ofx");
+                    import_file = ofx_matches.value_of("file").map(PathBuf::from);
+                }
+                ("json", Some(json_matches)) => {
+                    command = String::from("import json");
+                    import_file = json_matches.value_of("file").map(PathBuf::from);
+                    merge = json_matches.value_of("merge");
+                    replace = json_matches.is_present("replace");
+                }
+                ("qif", Some(qif_matches)) => {
+                    command = String::from("import qif");
+                    import_file = qif_matches.value_of("file").map(PathBuf::from);
+                    day_first = qif_matches.is_present("day-first");
+                }
+                _ => {
+                    return Err(From::from(
+                        "please choose a format to import, e.g. 'fund import csv'",
+                    ))
+                }
+            },
+            ("export", Some(export_matches)) => {
+                match export_matches.value_of("format") {
+                    Some("json") => command = String::from("export json"),
+                    format => journal_format = format,
+                }
+                output = export_matches.value_of("output").map(PathBuf::from);
+            }
+            ("profile", Some(profile_matches)) => match profile_matches.subcommand() {
+                ("create", Some(create_matches)) => {
+                    command = String::from("profile create");
+                    profile_name = create_matches.value_of("name");
+                }
+                ("use", Some(use_matches)) => {
+                    command = String::from("profile use");
+                    profile_name = use_matches.value_of("name");
+                }
+                _ => command = String::from("profile list"),
+            },
+            ("rate", Some(rate_matches)) => match rate_matches.subcommand() {
+                ("set", Some(set_matches)) => {
+                    command = String::from("rate set");
+                    currency = set_matches.value_of("from");
+                    to_currency = set_matches.value_of("to");
+                    rate = set_matches.value_of("rate");
+                }
+                _ => command = String::from("rate list"),
+            },
+            ("batch", Some(batch_matches)) => {
+                batch_file = batch_matches.value_of("file").map(PathBuf::from);
+                continue_on_error = batch_matches.is_present("continue-on-error");
+            }
+            ("completions", Some(completions_matches)) => {
+                completion_shell = completions_matches.value_of("shell").map(String::from);
+            }
+            ("_names", Some(names_matches)) => {
+                names = names_matches.value_of("kind").map(String::from);
+            }
+            ("alerts", Some(_)) | ("shell", Some(_)) | ("tui", Some(_)) => {}
             ("", None) => command = String::from("info"),
             _ => unreachable!(),
         }
+        let (memo, payee, date) = match matches.subcommand() {
+            ("spend", Some(transaction_matches))
+            | ("deposit", Some(transaction_matches))
+            | ("transfer", Some(transaction_matches)) => (
+                transaction_matches.value_of("memo").map(String::from),
+                transaction_matches.value_of("payee").map(String::from),
+                transaction_matches.value_of("date"),
+            ),
+            _ => (None, None, None),
+        };
 
-        let fund_name = fund_name.and_then(|x| Some(String::from(x)));
-        let transfer_name = transfer_name.and_then(|x| Some(String::from(x)));
-        let field = field.and_then(|x| Some(String::from(x)));
-        let amount = amount.map_or(Ok(None), |x| x.replace(".", "").parse::<i32>().map(Some))?;
-        let goal = goal.map_or(Ok(None), |x| x.replace(".", "").parse::<i32>().map(Some))?;
+        let fund_name = fund_name.map(String::from);
+        let transfer_name = transfer_name.map(String::from);
+        let field = field.map(String::from);
+        let amount = amount.map_or(Ok(None), |x| x.parse::<Amount>().map(Some))?;
+        let goal = goal.map_or(Ok(None), |x| x.parse::<Amount>().map(Some))?;
+        let currency = currency.map_or(Ok(None), |x| x.parse::<Currency>().map(Some))?;
+        let to_currency = to_currency.map_or(Ok(None), |x| x.parse::<Currency>().map(Some))?;
+        let rate = rate.map_or(Ok(None), |x| x.parse::<f64>().map(Some))?;
+        let period = period.map_or(Ok(Period::Month), str::parse)?;
+        let days = match days {
+            Some(days) => match days.parse() {
+                Ok(days) if (1..=MAX_GRAPH_DAYS).contains(&days) => days,
+                _ => {
+                    return Err(From::from(format!(
+                        "'{}' is not a number of days from 1 to {}",
+                        days, MAX_GRAPH_DAYS
+                    )))
+                }
+            },
+            None => 90,
+        };
+        let journal_format =
+            journal_format.map_or(Ok(None), |x| x.parse::<JournalFormat>().map(Some))?;
+        let merge = merge.map_or(Ok(None), |x| x.parse::<MergeStrategy>().map(Some))?;
+        let date = date.map_or(Ok(None), |x| {
+            parse_date(x, Local::now().date_naive()).map(Some)
+        })?;
+        let until = until.map_or(Ok(None), |x| {
+            parse_date(x, Local::now().date_naive()).map(Some)
+        })?;
+        let split = split
+            .into_iter()
+            .map(parse_split)
+            .collect::<Result<Vec<(String, Amount)>, Box<dyn Error + Send + Sync>>>()?;
+        if let Some(rate) = rate {
+            if !(rate.is_finite() && rate > 0.0) {
+                return Err(From::from("exchange rates must be positive numbers"));
+            }
+        }
 
         Ok(Config {
             configdir,
             fundfile,
+            historyfile,
+            ratesfile,
+            style: Style::new(&settings)?,
+            settings,
+            report,
             command,
             fund_name,
             transfer_name,
             field,
             amount,
             goal,
+            currency,
+            to_currency,
+            rate,
+            period,
+            days,
+            html,
+            svg,
+            import_file,
+            csv_profile,
+            day_first,
+            journal_format,
+            output,
+            merge,
+            replace,
+            profiles,
+            profile,
+            profile_name: profile_name.map(String::from),
+            batch_file,
+            continue_on_error,
+            dry_run: matches.is_present("dry-run"),
+            memo,
+            payee,
+            date,
+            split,
+            completion_shell,
+            names,
+            until,
         })
     }
 }
 
-pub fn run(config: Config) -> Result<(), libfund::FundManagerError> {
-    let mut funds = libfund::FundManager::load(&config.fundfile)?;
+/// The funds, history and exchange rates commands work on
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Data {
+    pub funds: libfund::FundManager,
+    pub history: History,
+    pub rates: Rates,
+}
+
+impl Data {
+    /// Reads the fund file, history and exchange rates named by `config`
+    ///
+    /// # Errors
+    ///
+    /// * When one of the files exists but could not be read or parsed
+    pub fn load(config: &Config) -> Result<Data, io::Error> {
+        Ok(Data {
+            funds: libfund::FundManager::load(&config.fundfile)?,
+            history: History::load(&config.historyfile)?,
+            rates: Rates::load(&config.ratesfile)?,
+        })
+    }
+
+    /// Writes the fund file, history and exchange rates named by `config`
+    ///
+    /// # Errors
+    ///
+    /// * When one of the files could not be written
+    pub fn save(&self, config: &Config) -> Result<(), io::Error> {
+        self.funds.save(&config.fundfile)?;
+        self.history.save(&config.historyfile)?;
+        self.rates.save(&config.ratesfile)
+    }
+}
 
+/// Parses a part of `spend --split`, a fund and an amount such as
+/// `grocery=12.50`
+fn parse_split(part: &str) -> Result<(String, Amount), Box<dyn Error + Send + Sync>> {
+    match part.rsplit_once('=') {
+        Some((name, amount)) if !name.is_empty() => Ok((String::from(name), amount.parse()?)),
+        _ => Err(From::from(format!(
+            "'{}' should be a fund and an amount, like grocery=12.50",
+            part
+        ))),
+    }
+}
+
+/// Resolves the funds of a split `spend` and converts each amount into the
+/// smallest unit of its fund's currency
+fn split_amounts(
+    funds: &libfund::FundManager,
+    split: &[(String, Amount)],
+    matching: NameMatching,
+) -> Result<Vec<(String, i32)>, libfund::FundManagerError> {
+    let mut amounts = Vec::new();
+    for (name, amount) in split {
+        let name = funds.resolve(name, matching)?;
+        let amount = minor_units(amount, funds.fund(name)?.currency)?;
+        amounts.push((String::from(name), amount));
+    }
+    Ok(amounts)
+}
+
+/// Converts an amount given on the command line into the smallest unit of
+/// `currency`
+fn minor_units(amount: &Amount, currency: Currency) -> Result<i32, io::Error> {
+    amount
+        .to_minor_units(currency)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.to_string()))
+}
+
+/// Parses a date given on the command line: `YYYY-MM-DD`, `today`,
+/// `yesterday`, or a number of days or weeks before `today` such as `-3d`
+/// or `-2w`
+///
+/// # Errors
+///
+/// * When `date` is none of these
+pub fn parse_date(date: &str, today: NaiveDate) -> Result<NaiveDate, String> {
+    let invalid = || format!("'{}' is not a date, use YYYY-MM-DD, yesterday or -3d", date);
+    match date {
+        "today" => return Ok(today),
+        "yesterday" => return Ok(today - Duration::days(1)),
+        _ => {}
+    }
+    if let Some(ago) = date.strip_prefix('-') {
+        let (count, weeks) = match (ago.strip_suffix('d'), ago.strip_suffix('w')) {
+            (Some(count), _) => (count, false),
+            (_, Some(count)) => (count, true),
+            _ => return Err(invalid()),
+        };
+        // Only digits, so a second sign such as `-+3d` is refused
+        if count.is_empty() || !count.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let count: i64 = count.parse().map_err(|_| invalid())?;
+        let days = if weeks {
+            count.checked_mul(7)
+        } else {
+            Some(count)
+        };
+        return days
+            .and_then(Duration::try_days)
+            .and_then(|ago| today.checked_sub_signed(ago))
+            .ok_or_else(invalid);
+    }
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| invalid())
+}
+
+/// Loads the data, runs the command described by `config` on it and saves
+/// the result, unless a hook vetoes it. On a dry run the changes are
+/// printed instead of saved.
+///
+/// Returns the status to exit with, which is 2 when `alerts` found any
+/// and 0 otherwise.
+pub fn run(config: Config) -> Result<i32, libfund::FundManagerError> {
     match config.command.as_str() {
-        "info" => match config.fund_name {
-            Some(name) => print_fund(&funds, &name)?,
-            None => print_all(&funds),
+        "batch" => return batch::run(&config).map(|_| 0),
+        "shell" => return shell::run(&config).map(|_| 0),
+        "tui" => return tui::run(&config).map(|_| 0),
+        "completions" => {
+            let shell = config.completion_shell.as_deref().unwrap_or("bash");
+            if let Some(script) = completions::script(shell) {
+                print!("{}", script);
+            }
+            return Ok(0);
+        }
+        "_names" => return Ok(completions::print_names(&config).map(|_| 0)?),
+        "alerts" => {
+            let data = Data::load(&config)?;
+            let found = print_alerts(&config, &data.funds, &data.history)?;
+            return Ok(if found > 0 { 2 } else { 0 });
+        }
+        _ => {}
+    }
+    let mut data = Data::load(&config)?;
+    let before = data.clone();
+    execute(&config, &mut data)?;
+    print!(
+        "{}",
+        hooks::check(&config, &config.command, &before, &data)?
+    );
+    if config.dry_run {
+        print_dry_run(&before, &data, &config.style);
+        return Ok(0);
+    }
+    data.save(&config)?;
+    print!("{}", hooks::exit(&config, &config.command, &before, &data)?);
+    Ok(0)
+}
+
+/// Runs a line of the shell or of a batch file, already split into words,
+/// on a copy of `data` and returns the copy
+///
+/// # Errors
+///
+/// * When the line is not a valid command, the command fails or a hook
+///   vetoes it, in which case `data` is left as it was
+pub fn execute_line(config: &Config, words: &[String], data: &Data) -> Result<Data, String> {
+    let line_config = match cli::parse_line(config, words)? {
+        Some(line_config) => line_config,
+        None => return Ok(data.clone()),
+    };
+    if let "shell" | "tui" | "batch" | "completions" | "_names" = line_config.command.as_str() {
+        return Err(format!(
+            "'{}' can't be run from the shell or a batch",
+            line_config.command
+        ));
+    }
+    if config.command == "batch" && writes_files(&line_config) {
+        return Err(format!(
+            "'{}' changes more than the funds, so it can't be run from a batch",
+            line_config.command
+        ));
+    }
+    let mut next = data.clone();
+    execute(&line_config, &mut next).map_err(|e| e.to_string())?;
+    print!(
+        "{}",
+        hooks::check(&line_config, &line_config.command, data, &next).map_err(|e| e.to_string())?
+    );
+    Ok(next)
+}
+
+/// Whether the command of `config` writes files besides the funds, which
+/// a batch could not take back when a later line fails
+fn writes_files(config: &Config) -> bool {
+    match config.command.as_str() {
+        "profile create" | "profile use" => true,
+        "report" => config.html.is_some() || config.svg.is_some(),
+        "export" | "export json" => config.output.is_some(),
+        _ => false,
+    }
+}
+
+/// Returns the name of the fund `name` refers to, if one was given
+fn resolve_name(
+    funds: &libfund::FundManager,
+    name: Option<&str>,
+    matching: NameMatching,
+) -> Result<Option<String>, libfund::FundNotFoundError> {
+    match name {
+        Some(name) => Ok(Some(String::from(funds.resolve(name, matching)?))),
+        None => Ok(None),
+    }
+}
+
+/// Runs the command described by `config` on `data` without saving it
+pub fn execute(config: &Config, data: &mut Data) -> Result<(), libfund::FundManagerError> {
+    let Data {
+        ref mut funds,
+        ref mut history,
+        ref mut rates,
+    } = *data;
+    let today = Local::now().date_naive();
+
+    let matching = if config.settings.get_bool("names.exact").unwrap_or(false) {
+        NameMatching::Exact
+    } else {
+        NameMatching::Loose
+    };
+    let fund_name = match config.command.as_str() {
+        "new" => config.fund_name.clone(),
+        _ => resolve_name(funds, config.fund_name.as_deref(), matching)?,
+    };
+    let transfer_name = match config.command.as_str() {
+        "transfer" => resolve_name(funds, config.transfer_name.as_deref(), matching)?,
+        _ => config.transfer_name.clone(),
+    };
+    let split = split_amounts(funds, &config.split, matching)?;
+
+    match config.command.as_str() {
+        "info" => match fund_name {
+            Some(ref name) => {
+                let fund = funds.fund(name)?;
+                let rows = [(name.as_str(), fund)];
+                println!("{}", config.report.render(&rows, true, None, &config.style));
+            }
+            None => {
+                let currency = config.currency.unwrap_or(config.style.currency);
+                let total = funds.total(currency, rates);
+                print_report(funds, &config.report, total.as_ref().ok(), &config.style);
+                if let (Err(e), true) = (total, funds.into_iter().count() > 1) {
+                    println!("Total unavailable: {}", e);
+                }
+            }
         },
-        "new" => match config.fund_name {
-            Some(name) => {
+        "new" => match fund_name {
+            Some(ref name) => {
+                let currency = config.currency.unwrap_or(config.style.currency);
                 let mut fund = libfund::Fund::new();
-                if let Some(amount) = config.amount {
-                    fund.with_amount(amount);
+                if let Some(ref amount) = config.amount {
+                    fund.with_amount(minor_units(amount, currency)?);
                 }
-                if let Some(goal) = config.goal {
-                    fund.with_goal(goal);
+                if let Some(ref goal) = config.goal {
+                    fund.with_goal(minor_units(goal, currency)?);
                 }
+                fund.with_currency(currency);
                 let fund = fund.build();
-                funds.add_fund(&name, fund)?;
-                print_fund(&funds, &name)?;
+                funds.add_fund(name, fund)?;
+                print_funds(funds, &[name], &config.style)?;
             }
             None => {
                 return Err(From::from(io::Error::new(
@@ -127,11 +700,34 @@ pub fn run(config: Config) -> Result<(), libfund::FundManagerError> {
                 )))
             }
         },
-        "spend" => match config.fund_name {
-            Some(name) => match config.amount {
-                Some(amount) => {
-                    funds.fund_mut(&name)?.spend(amount);
-                    print_fund(&funds, &name)?;
+        "spend" if !split.is_empty() => {
+            let parts: Vec<(&str, i32)> = split
+                .iter()
+                .map(|(name, amount)| (name.as_str(), *amount))
+                .collect();
+            let before = funds.clone();
+            let mut transaction = funds.spend_split(&parts, config.date.unwrap_or(today))?;
+            history.push(describe(&mut transaction, config).build());
+            // A fund named twice, or by two names matching it, is shown once
+            let mut names: Vec<&str> = parts.iter().map(|&(name, _)| name).collect();
+            names.sort_unstable();
+            names.dedup();
+            print_funds(funds, &names, &config.style)?;
+            for name in names {
+                print_crossed(config, name, before.fund(name)?, funds.fund(name)?)?;
+            }
+        }
+        "spend" => match fund_name {
+            Some(ref name) => match config.amount {
+                Some(ref amount) => {
+                    let before = *funds.fund(name)?;
+                    let amount = minor_units(amount, before.currency)?;
+                    funds.fund_mut(name)?.spend(amount);
+                    let mut transaction = Transaction::new(config.date.unwrap_or(today));
+                    transaction.with_leg(name, -amount);
+                    history.push(describe(&mut transaction, config).build());
+                    print_funds(funds, &[name], &config.style)?;
+                    print_crossed(config, name, &before, funds.fund(name)?)?;
                 }
                 None => {
                     return Err(From::from(io::Error::new(
@@ -147,11 +743,15 @@ pub fn run(config: Config) -> Result<(), libfund::FundManagerError> {
                 )))
             }
         },
-        "deposit" => match config.fund_name {
-            Some(name) => match config.amount {
-                Some(amount) => {
-                    funds.fund_mut(&name)?.deposit(amount);
-                    print_fund(&funds, &name)?;
+        "deposit" => match fund_name {
+            Some(ref name) => match config.amount {
+                Some(ref amount) => {
+                    let amount = minor_units(amount, funds.fund(name)?.currency)?;
+                    funds.fund_mut(name)?.deposit(amount);
+                    let mut transaction = Transaction::new(config.date.unwrap_or(today));
+                    transaction.with_leg(name, amount);
+                    history.push(describe(&mut transaction, config).build());
+                    print_funds(funds, &[name], &config.style)?;
                 }
                 None => {
                     return Err(From::from(io::Error::new(
@@ -167,14 +767,26 @@ pub fn run(config: Config) -> Result<(), libfund::FundManagerError> {
                 )))
             }
         },
-        "transfer" => match config.fund_name {
-            Some(name) => match config.transfer_name {
-                Some(transfer_name) => match config.amount {
-                    Some(amount) => {
-                        funds.fund_mut(&name)?.spend(amount);
-                        funds.fund_mut(&transfer_name)?.deposit(amount);
-                        print_fund(&funds, &name)?;
-                        print_fund(&funds, &transfer_name)?;
+        "transfer" => match fund_name {
+            Some(ref name) => match transfer_name {
+                Some(ref transfer_name) => match config.amount {
+                    Some(ref amount) => {
+                        let amount = minor_units(amount, funds.fund(name)?.currency)?;
+                        let date = config.date.unwrap_or(today);
+                        let mut transaction =
+                            funds.transfer(name, transfer_name, amount, rates, date)?;
+                        if let Some(rate) = transaction.rate {
+                            let from = funds.fund(name)?.currency;
+                            let to = funds.fund(transfer_name)?.currency;
+                            println!(
+                                "Converted {} to {} at a rate of {}",
+                                from,
+                                to,
+                                display_rate(rate)
+                            );
+                        }
+                        history.push(describe(&mut transaction, config).build());
+                        print_funds(funds, &[name, transfer_name], &config.style)?;
                     }
                     None => {
                         return Err(From::from(io::Error::new(
@@ -197,11 +809,12 @@ pub fn run(config: Config) -> Result<(), libfund::FundManagerError> {
                 )))
             }
         },
-        "rename" => match config.fund_name {
-            Some(name) => match config.transfer_name {
-                Some(transfer_name) => {
-                    funds.rename(&name, &transfer_name)?;
-                    print_fund(&funds, &transfer_name)?;
+        "rename" => match fund_name {
+            Some(ref name) => match transfer_name {
+                Some(ref transfer_name) => {
+                    funds.rename(name, transfer_name)?;
+                    history.rename_fund(name, transfer_name);
+                    print_funds(funds, &[transfer_name], &config.style)?;
                 }
                 None => {
                     return Err(From::from(io::Error::new(
@@ -217,13 +830,14 @@ pub fn run(config: Config) -> Result<(), libfund::FundManagerError> {
                 )))
             }
         },
-        "set" => match config.fund_name {
-            Some(name) => match config.amount {
-                Some(amount) => match config.field {
-                    Some(field) => {
+        "set" => match fund_name {
+            Some(ref name) => match config.amount {
+                Some(ref amount) => match config.field {
+                    Some(ref field) => {
+                        let amount = minor_units(amount, funds.fund(name)?.currency)?;
                         match field.as_str() {
-                            "amount" => funds.fund_mut(&name)?.amount = amount,
-                            "goal" => funds.fund_mut(&name)?.goal = amount,
+                            "amount" => funds.fund_mut(name)?.amount = amount,
+                            "goal" => funds.fund_mut(name)?.goal = amount,
                             _ => {
                                 return Err(From::from(io::Error::new(
                                     io::ErrorKind::InvalidInput,
@@ -231,7 +845,7 @@ pub fn run(config: Config) -> Result<(), libfund::FundManagerError> {
                                 )))
                             }
                         };
-                        print_fund(&funds, &name)?;
+                        print_funds(funds, &[name], &config.style)?;
                     }
                     None => {
                         return Err(From::from(io::Error::new(
@@ -254,6 +868,232 @@ pub fn run(config: Config) -> Result<(), libfund::FundManagerError> {
                 )))
             }
         },
+        "alerts" => {
+            print_alerts(config, funds, history)?;
+        }
+        "stats" => {
+            let stats = match fund_name {
+                Some(ref name) => vec![libfund::stats::fund_stats(
+                    name,
+                    funds.fund(name)?,
+                    history,
+                    config.period,
+                    today,
+                )],
+                None => libfund::stats::all_stats(funds, history, config.period, today),
+            };
+            for (index, fund_stats) in stats.iter().enumerate() {
+                if index > 0 {
+                    println!();
+                }
+                let currency = funds.fund(&fund_stats.name)?.currency;
+                print_stats(
+                    fund_stats,
+                    config.period,
+                    today,
+                    &config.style.money_for(currency),
+                );
+            }
+        }
+        "forecast" => {
+            let until = config.until.unwrap_or(today + Duration::days(90));
+            if until <= today {
+                return Err(From::from(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "the forecast has to end after today",
+                )));
+            }
+            let schedules = forecast::schedules(&config.settings, funds, matching, today)?;
+            let forecasts = libfund::forecast::forecast(funds, history, &schedules, today, until);
+            match fund_name {
+                Some(ref name) => {
+                    if let Some(fund_forecast) = forecasts
+                        .iter()
+                        .find(|fund_forecast| &fund_forecast.name == name)
+                    {
+                        forecast::print_fund_forecast(
+                            funds.fund(name)?,
+                            fund_forecast,
+                            &schedules,
+                            until,
+                            &config.style,
+                        );
+                    }
+                }
+                None => forecast::print_forecasts(funds, &forecasts, until, &config.style),
+            }
+        }
+        "graph" => match fund_name {
+            Some(ref name) => {
+                let fund = funds.fund(name)?;
+                chart::print_fund_graphs(name, fund, history, config.days, today, &config.style);
+            }
+            None => {
+                let from = Period::Month.start(today);
+                let currency = config.style.currency;
+                chart::print_overview(funds, history, rates, currency, from, today, &config.style)?;
+            }
+        },
+        "report" => {
+            let currency = config.currency.unwrap_or(config.style.currency);
+            let data = ReportData::new(funds, history, rates, currency, today, &config.style);
+            if let Some(ref path) = config.html {
+                fs::write(path, document::html(&data))?;
+                println!("Wrote HTML report to {}", path.display());
+            }
+            if let Some(ref path) = config.svg {
+                fs::write(path, document::svg(&data))?;
+                println!("Wrote SVG report to {}", path.display());
+            }
+        }
+        "import csv" | "import ofx" | "import qif" => match config.import_file {
+            Some(ref file) => {
+                let text = fs::read_to_string(file)?;
+                // OFX and QIF amounts are read in the default currency
+                let decimals = config.style.currency.minor_units();
+                let entries = match (config.command.as_str(), &config.csv_profile) {
+                    ("import ofx", _) => libfund::import::parse_ofx(&text, decimals)?,
+                    ("import qif", _) => {
+                        libfund::import::parse_qif(&text, config.day_first, decimals)?
+                    }
+                    (_, Some(profile)) => profile.parse(&text)?,
+                    (_, None) => {
+                        return Err(From::from(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            "please supply a profile describing the statement",
+                        )))
+                    }
+                };
+                let rules = Rules::load(&config.configdir.join("import").join("rules"))?;
+                let summary =
+                    import::import_entries(&entries, &rules, funds, history, &config.style)?;
+                print_import_summary(&summary);
+            }
+            None => {
+                return Err(From::from(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "please supply a statement to import",
+                )))
+            }
+        },
+        "export json" => {
+            let json = Backup::new(funds.clone(), history.clone(), rates.clone()).to_json();
+            match config.output {
+                Some(ref path) => fs::write(path, json)?,
+                None => print!("{}", json),
+            }
+        }
+        "import json" => match config.import_file {
+            Some(ref file) => {
+                let incoming = Backup::from_json(&fs::read_to_string(file)?)?;
+                let is_empty = (&*funds).into_iter().next().is_none() && history.is_empty();
+                let strategy = match config.merge {
+                    _ if config.replace => None,
+                    Some(strategy) => Some(strategy),
+                    None if is_empty => Some(MergeStrategy::Error),
+                    None => {
+                        return Err(From::from(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            "there are funds already, please choose --merge or --replace",
+                        )))
+                    }
+                };
+                let backup = match strategy {
+                    Some(strategy) => {
+                        let mut backup = Backup::new(funds.clone(), history.clone(), rates.clone());
+                        print_merge_summary(&backup.merge(incoming, strategy)?);
+                        backup
+                    }
+                    None => {
+                        println!("Replaced all funds, history and exchange rates");
+                        incoming
+                    }
+                };
+                *funds = backup.funds;
+                *history = backup.history;
+                *rates = backup.rates;
+            }
+            None => {
+                return Err(From::from(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "please supply a backup to import",
+                )))
+            }
+        },
+        "export" => match config.journal_format {
+            Some(format) => {
+                let prefix = config
+                    .settings
+                    .get("export.account")
+                    .unwrap_or(DEFAULT_EXPORT_ACCOUNT);
+                let journal = libfund::export::journal(funds, history, format, prefix, today);
+                match config.output {
+                    Some(ref path) => fs::write(path, journal)?,
+                    None => print!("{}", journal),
+                }
+            }
+            None => {
+                return Err(From::from(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "please supply a format to export to",
+                )))
+            }
+        },
+        "profile list" => {
+            for name in config.profiles.list()? {
+                let marker = if name == config.profile { "*" } else { " " };
+                println!("{} {}", marker, name);
+            }
+        }
+        "profile create" => match config.profile_name {
+            Some(ref name) if config.dry_run => println!("Would create profile '{}'", name),
+            Some(ref name) => {
+                config.profiles.create(name)?;
+                println!(
+                    "Created profile '{}'. Switch to it with 'fund profile use {}'",
+                    name, name
+                );
+            }
+            None => {
+                return Err(From::from(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "please supply a name for the profile",
+                )))
+            }
+        },
+        "profile use" => match config.profile_name {
+            Some(ref name) if config.dry_run => {
+                config.profiles.check_exists(name)?;
+                println!("Would use profile '{}'", name);
+            }
+            Some(ref name) => {
+                config.profiles.use_profile(name)?;
+                println!("Now using profile '{}'", name);
+            }
+            None => {
+                return Err(From::from(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "please supply the profile to use",
+                )))
+            }
+        },
+        "rate set" => match (config.currency, config.to_currency, config.rate) {
+            (Some(from), Some(to), Some(rate)) => {
+                rates.set(from, to, rate);
+                println!("1 {} = {} {}", from, display_rate(rate), to);
+            }
+            _ => {
+                return Err(From::from(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "please supply two currencies and a rate",
+                )))
+            }
+        },
+        "rate list" => {
+            for (from, to, rate) in rates.iter() {
+                println!("1 {} = {} {}", from, display_rate(rate), to);
+            }
+        }
         _ => {
             return Err(From::from(io::Error::new(
                 io::ErrorKind::InvalidInput,
@@ -261,22 +1101,385 @@ pub fn run(config: Config) -> Result<(), libfund::FundManagerError> {
             )))
         }
     }
-    funds.save(&config.fundfile)?;
     Ok(())
 }
 
-pub fn print_fund(funds: &libfund::FundManager, name: &str) -> Result<(), libfund::FundNotFoundError> {
-    let fund = funds.fund(name)?;
-    let mut name = String::from(name);
-    name.push(':');
-    println!("{:>10} {}", name, fund);
+/// Adds the payee and memo given on the command line to `transaction`
+fn describe<'a>(transaction: &'a mut Transaction, config: &Config) -> &'a mut Transaction {
+    if let Some(ref payee) = config.payee {
+        transaction.with_payee(payee);
+    }
+    if let Some(ref memo) = config.memo {
+        transaction.with_memo(memo);
+    }
+    transaction
+}
+
+/// Prints the statistics of a single fund for the `period` containing `today`
+pub fn print_stats(stats: &FundStats, period: Period, today: NaiveDate, money: &MoneyFormat) {
+    let (label, previous) = match period {
+        Period::Month => (today.format("%B %Y").to_string(), "month"),
+        Period::Quarter => (
+            format!("Q{} {}", (today.month() - 1) / 3 + 1, today.year()),
+            "quarter",
+        ),
+        Period::Year => (today.format("%Y").to_string(), "year"),
+    };
+    println!("{} ({})", stats.name, label);
+    println!("  {:<18} {:>12}", "In:", money.format(stats.money_in));
+    println!("  {:<18} {:>12}", "Out:", money.format(stats.money_out));
+    let change = match stats.spending_change() {
+        Some(change) => format!("  ({:+.0}% from previous {})", change * 100.0, previous),
+        None => String::new(),
+    };
+    println!(
+        "  {:<18} {:>12}{}",
+        "Spent:",
+        money.format(stats.spent),
+        change
+    );
+    println!(
+        "  {:<18} {:>12}",
+        "Average monthly:",
+        money.format(stats.average_monthly_spend)
+    );
+    match stats.runway_days {
+        Some(days) => println!("  {:<18} {:>12}", "Runway:", format!("{} days", days)),
+        None => println!("  {:<18} {:>12}", "Runway:", "-"),
+    }
+    for (index, &(date, amount)) in stats.largest_expenses.iter().enumerate() {
+        let heading = if index == 0 { "Largest expenses:" } else { "" };
+        println!("  {:<18} {:>12}  {}", heading, money.format(amount), date);
+    }
+}
+
+/// Prints how many statement entries were imported, skipped as
+/// duplicates and left unassigned
+pub fn print_import_summary(summary: &ImportSummary) {
+    let plural = if summary.imported == 1 { "" } else { "s" };
+    println!("Imported {} transaction{}", summary.imported, plural);
+    if summary.duplicates > 0 {
+        println!("Skipped {} already imported", summary.duplicates);
+    }
+    if summary.unassigned > 0 {
+        println!(
+            "Left {} unassigned, add rules for them and import again",
+            summary.unassigned
+        );
+    }
+}
+
+/// Prints what happened to each fund during a merge and how many
+/// transactions were added
+pub fn print_merge_summary(summary: &MergeSummary) {
+    let report = &summary.funds;
+    for name in &report.added {
+        println!("Added {}", name);
+    }
+    for name in &report.kept {
+        println!("Skipped {}, a fund with that name already exists", name);
+    }
+    for name in &report.replaced {
+        println!("Overwrote {}", name);
+    }
+    for name in &report.summed {
+        println!("Added the amount of {} to the existing fund", name);
+    }
+    for (old_name, new_name) in &report.renamed {
+        println!(
+            "Added {} as {}, a fund with that name already exists",
+            old_name, new_name
+        );
+    }
+    let plural = if summary.transactions == 1 { "" } else { "s" };
+    println!(
+        "Added {} transaction{} to the history",
+        summary.transactions, plural
+    );
+}
+
+/// Formats an exchange rate with at most six decimals
+fn display_rate(rate: f64) -> String {
+    let rate = format!("{:.6}", rate);
+    String::from(rate.trim_end_matches('0').trim_end_matches('.'))
+}
+
+/// Prints one row per named fund, without the header and totals
+pub fn print_funds(
+    funds: &libfund::FundManager,
+    names: &[&str],
+    style: &Style,
+) -> Result<(), libfund::FundNotFoundError> {
+    let mut rows = Vec::new();
+    for name in names {
+        rows.push((*name, funds.fund(name)?));
+    }
+    println!("{}", Report::default().render(&rows, false, None, style));
+    Ok(())
+}
+
+/// Reads the `alert.<fund>` settings, the threshold each fund should stay
+/// above, in that fund's currency. Alerts for funds that don't exist are
+/// skipped.
+///
+/// # Errors
+///
+/// * When a threshold is neither an amount of its fund's currency nor a
+///   percentage
+pub fn alert_thresholds(
+    settings: &Settings,
+    funds: &libfund::FundManager,
+) -> Result<BTreeMap<String, Threshold>, io::Error> {
+    let mut thresholds = BTreeMap::new();
+    for (name, value) in settings.with_prefix("alert.") {
+        let fund = match funds.fund(name) {
+            Ok(fund) => fund,
+            Err(_) => continue,
+        };
+        let threshold = Threshold::parse(value, fund.currency).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("alert.{}: {}", name, e))
+        })?;
+        thresholds.insert(String::from(name), threshold);
+    }
+    Ok(thresholds)
+}
+
+/// Describes `alert` for the fund called `name`
+pub fn describe_alert(name: &str, fund: &libfund::Fund, alert: Alert, style: &Style) -> String {
+    let money = style.money_for(fund.currency);
+    let format = |amount: i32| money.format(i64::from(amount));
+    match alert {
+        Alert::Overdrawn => format!("{} is overdrawn at {}", name, format(fund.amount)),
+        Alert::Low { limit } => format!(
+            "{} is below its alert of {} at {}",
+            name,
+            format(limit),
+            format(fund.amount)
+        ),
+        Alert::GoalReached => format!("{} reached its goal of {}", name, format(fund.goal)),
+    }
+}
+
+fn alert_color(alert: Alert) -> Color {
+    match alert {
+        Alert::Overdrawn => Color::Red,
+        Alert::Low { .. } => Color::Yellow,
+        Alert::GoalReached => Color::Green,
+    }
+}
+
+/// Prints every alert and returns how many there were
+///
+/// # Errors
+///
+/// * When an alert setting is invalid
+pub fn print_alerts(
+    config: &Config,
+    funds: &libfund::FundManager,
+    history: &History,
+) -> Result<usize, io::Error> {
+    let alerts =
+        libfund::alerts::alerts(funds, history, &alert_thresholds(&config.settings, funds)?);
+    if alerts.is_empty() {
+        println!("No alerts");
+    }
+    for (name, alert) in &alerts {
+        if let Ok(fund) = funds.fund(name) {
+            let line = describe_alert(name, fund, *alert, &config.style);
+            println!(
+                "{}",
+                term::paint(&line, alert_color(*alert), config.style.color)
+            );
+        }
+    }
+    Ok(alerts.len())
+}
+
+/// Warns when spending took the fund called `name` below zero or below
+/// its alert
+fn print_crossed(
+    config: &Config,
+    name: &str,
+    before: &libfund::Fund,
+    after: &libfund::Fund,
+) -> Result<(), io::Error> {
+    let threshold = match config.settings.get(&format!("alert.{}", name)) {
+        Some(value) => Some(Threshold::parse(value, after.currency).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("alert.{}: {}", name, e))
+        })?),
+        None => None,
+    };
+    if let Some(alert) = libfund::alerts::crossed(before, after, threshold) {
+        let line = format!(
+            "Warning: {}",
+            describe_alert(name, after, alert, &config.style)
+        );
+        println!(
+            "{}",
+            term::paint(&line, alert_color(alert), config.style.color)
+        );
+    }
     Ok(())
 }
 
-pub fn print_all(funds: &libfund::FundManager) {
-    for fund in funds.into_iter() {
-        let mut name = fund.0.to_owned();
-        name.push(':');
-        println!("{:>10} {}", name, fund.1)
+/// Prints a line for every fund that was added, removed or changed
+/// between `before` and `after`
+pub fn print_diff(before: &libfund::FundManager, after: &libfund::FundManager, style: &Style) {
+    for (name, _) in before {
+        if after.fund(name).is_err() {
+            println!(
+                "{}",
+                term::paint(&format!("- {}", name), Color::Red, style.color)
+            );
+        }
+    }
+    for (name, fund) in after {
+        let money = style.money_for(fund.currency);
+        match before.fund(name) {
+            Err(_) => {
+                let line = format!("+ {}  {}", name, fund.summary(&money));
+                println!("{}", term::paint(&line, Color::Green, style.color));
+            }
+            Ok(old) if old != fund => {
+                let mut changes = Vec::new();
+                if old.amount != fund.amount {
+                    changes.push(format!(
+                        "amount {} -> {}",
+                        money.format(i64::from(old.amount)),
+                        money.format(i64::from(fund.amount))
+                    ));
+                }
+                if old.goal != fund.goal {
+                    changes.push(format!(
+                        "goal {} -> {}",
+                        money.format(i64::from(old.goal)),
+                        money.format(i64::from(fund.goal))
+                    ));
+                }
+                if old.currency != fund.currency {
+                    changes.push(format!("currency {} -> {}", old.currency, fund.currency));
+                }
+                let line = format!("~ {}  {}", name, changes.join(", "));
+                println!("{}", term::paint(&line, Color::Yellow, style.color));
+            }
+            Ok(_) => {}
+        }
+    }
+}
+
+/// Prints the funds that changed between `before` and `after`, followed
+/// by how many transactions were added and whether exchange rates changed
+pub fn print_changes(before: &Data, after: &Data, style: &Style) {
+    if before == after {
+        println!("No changes");
+        return;
+    }
+    print_diff(&before.funds, &after.funds, style);
+    let added = after.history.len().saturating_sub(before.history.len());
+    match added {
+        0 => {}
+        1 => println!("1 transaction to record"),
+        _ => println!("{} transactions to record", added),
+    }
+    if before.rates != after.rates {
+        println!("Exchange rates changed");
+    }
+}
+
+/// Prints what a dry run would have changed
+pub fn print_dry_run(before: &Data, after: &Data, style: &Style) {
+    println!();
+    println!("Dry run, nothing was saved. Changes:");
+    print_changes(before, after, style);
+}
+
+/// Prints all funds as described by `report`, followed by their `total`
+/// if there is more than one fund
+pub fn print_report(
+    funds: &libfund::FundManager,
+    report: &Report,
+    total: Option<&libfund::Fund>,
+    style: &Style,
+) {
+    let mut funds: Vec<(&str, &libfund::Fund)> = funds
+        .into_iter()
+        .map(|(name, fund)| (name.as_str(), fund))
+        .collect();
+    report.sort(&mut funds);
+    let total = if funds.len() > 1 { total } else { None };
+    println!("{}", report.render(&funds, true, total, style));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_date, parse_split, split_amounts};
+    use chrono::NaiveDate;
+    use libfund::{Fund, FundManager, NameMatching};
+
+    fn day(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn parses_dates() {
+        let today = day(2026, 10, 18);
+        assert_eq!(parse_date("2026-01-31", today), Ok(day(2026, 1, 31)));
+        assert_eq!(parse_date("today", today), Ok(today));
+        assert_eq!(parse_date("yesterday", today), Ok(day(2026, 10, 17)));
+        assert_eq!(parse_date("-3d", today), Ok(day(2026, 10, 15)));
+        assert_eq!(parse_date("-2w", today), Ok(day(2026, 10, 4)));
+        assert_eq!(parse_date("-0d", today), Ok(today));
+    }
+
+    #[test]
+    fn rejects_invalid_dates() {
+        let today = day(2026, 10, 18);
+        for invalid in &[
+            "",
+            "-",
+            "-d",
+            "-3",
+            "-3m",
+            "--3d",
+            "-+-3d",
+            "-+3d",
+            "-+3w",
+            "-3é",
+            "-é",
+            "-éd",
+            "-3 d",
+            "2026-02-30",
+            "tomorrow",
+            "-99999999999d",
+            "-9999999999999999w",
+            "-99999999999999999999d",
+        ] {
+            assert!(parse_date(invalid, today).is_err(), "{} parsed", invalid);
+        }
+    }
+
+    #[test]
+    fn parses_split_amounts_in_each_funds_currency() {
+        let mut funds = FundManager::new();
+        funds.add_fund("grocery", Fund::new().build()).unwrap();
+        let yen = "JPY".parse().unwrap();
+        funds
+            .add_fund("trip", Fund::new().with_currency(yen).build())
+            .unwrap();
+        let split = |parts: &[&str]| {
+            let parts: Vec<_> = parts
+                .iter()
+                .map(|part| parse_split(part).unwrap())
+                .collect();
+            split_amounts(&funds, &parts, NameMatching::Loose)
+        };
+        assert_eq!(
+            split(&["gro=82.1", "trip=500"]).unwrap(),
+            vec![(String::from("grocery"), 8210), (String::from("trip"), 500)]
+        );
+        assert!(split(&["trip=82.1"]).is_err());
+        for invalid in &["grocery", "=5", "grocery=", "grocery=1,000", "grocery=$5"] {
+            assert!(parse_split(invalid).is_err(), "{} parsed", invalid);
+        }
     }
 }
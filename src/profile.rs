@@ -0,0 +1,143 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The profile whose files live directly in the config and data
+/// directories, as they did before profiles existed
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Named sets of funds, each with its own fund file, history and
+/// settings, such as one for a household budget and one for a business.
+///
+/// Named profiles keep their fund file and history in
+/// `<data dir>/profiles/<name>` and their settings in
+/// `<config dir>/profiles/<name>/fundrc`, which is read after the main
+/// `fundrc` so it only needs to hold what differs. The profile used when
+/// none is given is remembered in `<config dir>/profile`.
+#[derive(Debug, Clone)]
+pub struct Profiles {
+    configdir: PathBuf,
+    datadir: PathBuf,
+}
+
+impl Profiles {
+    pub fn new(configdir: &Path, datadir: &Path) -> Profiles {
+        Profiles {
+            configdir: configdir.to_path_buf(),
+            datadir: datadir.to_path_buf(),
+        }
+    }
+
+    /// Returns the profile used when none is given
+    ///
+    /// # Errors
+    ///
+    /// * When the file remembering it exists but could not be read
+    pub fn current(&self) -> Result<String, io::Error> {
+        match fs::read_to_string(self.configdir.join("profile")) {
+            Ok(name) if !name.trim().is_empty() => Ok(String::from(name.trim())),
+            Ok(_) => Ok(String::from(DEFAULT_PROFILE)),
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(String::from(DEFAULT_PROFILE)),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Makes `name` the profile used when none is given
+    ///
+    /// # Errors
+    ///
+    /// * When there is no profile called `name`
+    /// * When the file remembering it could not be written
+    pub fn use_profile(&self, name: &str) -> Result<(), io::Error> {
+        self.check_exists(name)?;
+        fs::create_dir_all(&self.configdir)?;
+        fs::write(self.configdir.join("profile"), format!("{}\n", name))
+    }
+
+    /// Returns the names of every profile, starting with the default one
+    ///
+    /// # Errors
+    ///
+    /// * When the profiles directory exists but could not be read
+    pub fn list(&self) -> Result<Vec<String>, io::Error> {
+        let mut names = Vec::new();
+        match fs::read_dir(self.datadir.join("profiles")) {
+            Ok(entries) => {
+                for entry in entries {
+                    let entry = entry?;
+                    if entry.file_type()?.is_dir() {
+                        names.push(entry.file_name().to_string_lossy().into_owned());
+                    }
+                }
+            }
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        names.sort();
+        names.insert(0, String::from(DEFAULT_PROFILE));
+        Ok(names)
+    }
+
+    /// Creates an empty profile called `name`
+    ///
+    /// # Errors
+    ///
+    /// * When `name` is not a valid profile name or is already taken
+    /// * When the profile's directories could not be created
+    pub fn create(&self, name: &str) -> Result<(), io::Error> {
+        if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("'{}' can't be used as a profile name", name),
+            ));
+        }
+        if self.exists(name) {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("profile '{}' already exists", name),
+            ));
+        }
+        fs::create_dir_all(self.datadir.join("profiles").join(name))?;
+        fs::create_dir_all(self.configdir.join("profiles").join(name))
+    }
+
+    /// Returns true if there is a profile called `name`
+    pub fn exists(&self, name: &str) -> bool {
+        name == DEFAULT_PROFILE || self.datadir.join("profiles").join(name).is_dir()
+    }
+
+    /// Returns an error unless there is a profile called `name`
+    pub fn check_exists(&self, name: &str) -> Result<(), io::Error> {
+        if self.exists(name) {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "no profile called '{}'. Create it with 'fund profile create {}'",
+                    name, name
+                ),
+            ))
+        }
+    }
+
+    /// Returns the fund file of the profile called `name`. Its history is
+    /// kept beside it.
+    pub fn fundfile(&self, name: &str) -> PathBuf {
+        if name == DEFAULT_PROFILE {
+            self.datadir.join("fund")
+        } else {
+            self.datadir.join("profiles").join(name).join("fund")
+        }
+    }
+
+    /// Returns the settings file read after the main `fundrc` for the
+    /// profile called `name`, if it has one of its own
+    pub fn settings_file(&self, name: &str) -> Option<PathBuf> {
+        if name == DEFAULT_PROFILE {
+            None
+        } else {
+            Some(self.configdir.join("profiles").join(name).join("fundrc"))
+        }
+    }
+}
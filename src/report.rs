@@ -0,0 +1,368 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::str::FromStr;
+
+use libfund::money::MoneyFormat;
+use libfund::Fund;
+
+use settings::Settings;
+use term;
+use term::{Color, Style};
+
+/// How many characters wide the bar in the progress column is
+const BAR_WIDTH: usize = 20;
+
+/// A piece of fund information that can be shown in a report
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Column {
+    Name,
+    Amount,
+    Goal,
+    Remaining,
+    Progress,
+    /// The fund's one line status, as printed by its `Display` impl
+    Summary,
+}
+
+impl Column {
+    /// Returns the heading shown above the column
+    pub fn header(self) -> &'static str {
+        match self {
+            Column::Name => "Name",
+            Column::Amount => "Amount",
+            Column::Goal => "Goal",
+            Column::Remaining => "Remaining",
+            Column::Progress => "Progress",
+            Column::Summary => "Summary",
+        }
+    }
+
+    fn right_aligned(self) -> bool {
+        match self {
+            Column::Amount | Column::Goal | Column::Remaining => true,
+            Column::Name | Column::Progress | Column::Summary => false,
+        }
+    }
+
+    fn cell(self, name: &str, fund: &Fund, money: &MoneyFormat) -> String {
+        match self {
+            Column::Name => String::from(name),
+            Column::Amount => money.format(i64::from(fund.amount)),
+            Column::Goal => money.format(i64::from(fund.goal)),
+            Column::Remaining => money.format(fund.remaining()),
+            Column::Progress => format!(
+                "{} {:>4.0}%",
+                term::progress_bar(fund.progress(), BAR_WIDTH),
+                fund.progress() * 100.0
+            ),
+            Column::Summary => fund.summary(money),
+        }
+    }
+
+    fn color(self, fund: &Fund) -> Option<Color> {
+        match self {
+            Column::Amount | Column::Progress => Some(status_color(fund)),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Column {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Column, String> {
+        match s {
+            "name" => Ok(Column::Name),
+            "amount" => Ok(Column::Amount),
+            "goal" => Ok(Column::Goal),
+            "remaining" => Ok(Column::Remaining),
+            "progress" => Ok(Column::Progress),
+            "summary" => Ok(Column::Summary),
+            _ => Err(format!("unknown column '{}'", s)),
+        }
+    }
+}
+
+/// What to order funds by in a report
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum SortKey {
+    Name,
+    Amount,
+    Goal,
+    Remaining,
+    Progress,
+}
+
+impl FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<SortKey, String> {
+        match s {
+            "name" => Ok(SortKey::Name),
+            "amount" => Ok(SortKey::Amount),
+            "goal" => Ok(SortKey::Goal),
+            "remaining" => Ok(SortKey::Remaining),
+            "progress" => Ok(SortKey::Progress),
+            _ => Err(format!("unknown sort key '{}'", s)),
+        }
+    }
+}
+
+/// Describes which columns to show and how to order the funds when
+/// listing them
+#[derive(Debug, PartialEq, Clone)]
+pub struct Report {
+    pub columns: Vec<Column>,
+    pub sort: SortKey,
+    pub reverse: bool,
+}
+
+impl Default for Report {
+    fn default() -> Report {
+        Report {
+            columns: vec![
+                Column::Name,
+                Column::Amount,
+                Column::Goal,
+                Column::Remaining,
+                Column::Progress,
+            ],
+            sort: SortKey::Name,
+            reverse: false,
+        }
+    }
+}
+
+impl Report {
+    /// Builds the report called `name` from the `report.<name>.columns`,
+    /// `report.<name>.sort` and `report.<name>.reverse` settings. Any of
+    /// them left out fall back to the default report.
+    ///
+    /// # Errors
+    ///
+    /// * When no setting for the report exists
+    /// * When a column or sort key is not recognized
+    pub fn from_settings(
+        settings: &Settings,
+        name: &str,
+    ) -> Result<Report, Box<dyn Error + Send + Sync>> {
+        let columns = settings.get(&format!("report.{}.columns", name));
+        let sort = settings.get(&format!("report.{}.sort", name));
+        let reverse = settings.get_bool(&format!("report.{}.reverse", name));
+        if columns.is_none() && sort.is_none() && reverse.is_none() {
+            return Err(From::from(format!(
+                "no report named '{}' in the config file",
+                name
+            )));
+        }
+
+        let mut report = Report::default();
+        if let Some(columns) = columns {
+            report.columns = parse_columns(columns)?;
+        }
+        if let Some(sort) = sort {
+            report.sort = sort.parse()?;
+        }
+        if let Some(reverse) = reverse {
+            report.reverse = reverse;
+        }
+        Ok(report)
+    }
+
+    /// Sorts `funds` in place according to the report's sort key, falling
+    /// back to the fund name to break ties
+    pub fn sort(&self, funds: &mut [(&str, &Fund)]) {
+        let key = self.sort;
+        funds.sort_by(|a, b| {
+            let ordering = match key {
+                SortKey::Name => Ordering::Equal,
+                SortKey::Amount => a.1.amount.cmp(&b.1.amount),
+                SortKey::Goal => a.1.goal.cmp(&b.1.goal),
+                SortKey::Remaining => a.1.remaining().cmp(&b.1.remaining()),
+                SortKey::Progress => {
+                    a.1.progress()
+                        .partial_cmp(&b.1.progress())
+                        .unwrap_or(Ordering::Equal)
+                }
+            };
+            ordering.then_with(|| a.0.cmp(b.0))
+        });
+        if self.reverse {
+            funds.reverse();
+        }
+    }
+
+    /// Renders `funds` as a table with one row per fund. Columns are
+    /// as wide as their widest cell. A bold header row is shown when
+    /// `header` is true, and a footer row when a `total` is given.
+    pub fn render(
+        &self,
+        funds: &[(&str, &Fund)],
+        header: bool,
+        total: Option<&Fund>,
+        style: &Style,
+    ) -> String {
+        let mut rows: Vec<(Vec<String>, Option<&Fund>)> = Vec::new();
+        if header {
+            let cells = self
+                .columns
+                .iter()
+                .map(|column| String::from(column.header()));
+            rows.push((cells.collect(), None));
+        }
+        for &(name, fund) in funds {
+            let money = style.money_for(fund.currency);
+            let cells = self
+                .columns
+                .iter()
+                .map(|column| column.cell(name, fund, &money));
+            rows.push((cells.collect(), Some(fund)));
+        }
+        let footer = total.map(|total| {
+            let money = style.money_for(total.currency);
+            let cells = self
+                .columns
+                .iter()
+                .map(|column| column.cell("Total", total, &money));
+            cells.collect::<Vec<String>>()
+        });
+
+        let mut widths: Vec<usize> = self.columns.iter().map(|_| 0).collect();
+        for cells in rows.iter().map(|row| &row.0).chain(footer.iter()) {
+            for (width, cell) in widths.iter_mut().zip(cells) {
+                *width = (*width).max(term::width(cell));
+            }
+        }
+
+        let mut lines: Vec<String> = Vec::new();
+        for (index, (cells, fund)) in rows.iter().enumerate() {
+            let line = self.render_row(cells, &widths, *fund, style.color);
+            if header && index == 0 {
+                lines.push(term::paint(&line, Color::Bold, style.color));
+            } else {
+                lines.push(line);
+            }
+        }
+        if let Some(cells) = footer {
+            let rule_width = widths.iter().sum::<usize>() + 2 * (widths.len() - 1);
+            lines.push("-".repeat(rule_width));
+            lines.push(self.render_row(&cells, &widths, total, style.color));
+        }
+        lines.join("\n")
+    }
+
+    fn render_row(
+        &self,
+        cells: &[String],
+        widths: &[usize],
+        fund: Option<&Fund>,
+        color: bool,
+    ) -> String {
+        let mut line = String::new();
+        for (i, column) in self.columns.iter().enumerate() {
+            let padding = " ".repeat(widths[i] - term::width(&cells[i]));
+            let cell = match fund.and_then(|fund| column.color(fund)) {
+                Some(fund_color) => term::paint(&cells[i], fund_color, color),
+                None => cells[i].clone(),
+            };
+            if i > 0 {
+                line.push_str("  ");
+            }
+            if column.right_aligned() {
+                line.push_str(&padding);
+                line.push_str(&cell);
+            } else {
+                line.push_str(&cell);
+                if i + 1 < self.columns.len() {
+                    line.push_str(&padding);
+                }
+            }
+        }
+        line
+    }
+}
+
+/// Returns red for overdrawn funds, yellow for funds below their goal
+/// and green for funds that have met it
+pub fn status_color(fund: &Fund) -> Color {
+    if fund.amount < 0 {
+        Color::Red
+    } else if fund.amount < fund.goal {
+        Color::Yellow
+    } else {
+        Color::Green
+    }
+}
+
+/// Parses a comma separated list of column names
+pub fn parse_columns(columns: &str) -> Result<Vec<Column>, String> {
+    columns
+        .split(',')
+        .map(|column| column.trim().parse())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_columns, Column, Report, SortKey};
+    use libfund::Fund;
+    use settings::Settings;
+
+    #[test]
+    fn parses_columns() {
+        assert_eq!(
+            parse_columns("name, progress,amount").unwrap(),
+            vec![Column::Name, Column::Progress, Column::Amount]
+        );
+        assert_eq!(
+            parse_columns("name,colour").unwrap_err(),
+            "unknown column 'colour'"
+        );
+        assert!(parse_columns("").is_err());
+    }
+
+    #[test]
+    fn reads_reports_from_settings() {
+        let mut settings = Settings::new();
+        settings.set("report.low.sort", "remaining");
+        settings.set("report.low.reverse", "yes");
+        let report = Report::from_settings(&settings, "low").unwrap();
+        assert_eq!(report.columns, Report::default().columns);
+        assert_eq!(report.sort, SortKey::Remaining);
+        assert!(report.reverse);
+
+        assert!(Report::from_settings(&settings, "high").is_err());
+        settings.set("report.bad.sort", "size");
+        assert!(Report::from_settings(&settings, "bad").is_err());
+    }
+
+    #[test]
+    fn sorts_funds() {
+        let fund = |amount| Fund::new().with_amount(amount).build();
+        let (car, rent, trip) = (fund(500), fund(100), fund(500));
+        let mut funds = vec![("trip", &trip), ("rent", &rent), ("car", &car)];
+        let report = Report {
+            sort: SortKey::Amount,
+            reverse: true,
+            ..Report::default()
+        };
+        report.sort(&mut funds);
+        let names: Vec<&str> = funds.iter().map(|fund| fund.0).collect();
+        assert_eq!(names, ["trip", "car", "rent"]);
+
+        // Far overdrawn funds would overflow an i32 of what remains
+        let overdrawn = Fund::new()
+            .with_amount(i32::MIN)
+            .with_goal(i32::MAX)
+            .build();
+        let saved = Fund::new().with_amount(100).with_goal(i32::MAX).build();
+        let mut funds = vec![("overdrawn", &overdrawn), ("saved", &saved)];
+        let report = Report {
+            sort: SortKey::Remaining,
+            ..Report::default()
+        };
+        report.sort(&mut funds);
+        let names: Vec<&str> = funds.iter().map(|fund| fund.0).collect();
+        assert_eq!(names, ["saved", "overdrawn"]);
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io;
+use std::io::prelude::*;
+use std::io::BufReader;
+use std::path::Path;
+
+/// Options read from the `fundrc` file in the config directory.
+///
+/// The file holds one `key = value` pair per line, TaskWarrior style.
+/// Blank lines and lines starting with `#` are ignored.
+#[derive(Debug, Default, Clone)]
+pub struct Settings {
+    values: BTreeMap<String, String>,
+}
+
+impl Settings {
+    pub fn new() -> Settings {
+        Settings {
+            values: BTreeMap::new(),
+        }
+    }
+
+    /// Reads settings from `path`. A missing file is not an error and
+    /// results in empty settings.
+    ///
+    /// # Errors
+    ///
+    /// * When the file exists but could not be read
+    /// * When a line is not a `key = value` pair
+    pub fn load(path: &Path) -> Result<Settings, io::Error> {
+        let mut settings = Settings::new();
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(settings),
+            Err(e) => return Err(e),
+        };
+
+        for (number, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.find('=') {
+                Some(index) => {
+                    let (key, value) = line.split_at(index);
+                    settings.set(key.trim(), value[1..].trim());
+                }
+                None => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("{:?} line {}: expected 'key = value'", path, number + 1),
+                    ))
+                }
+            }
+        }
+        Ok(settings)
+    }
+
+    /// Sets `key` to `value`, replacing any previous value
+    pub fn set(&mut self, key: &str, value: &str) {
+        self.values.insert(String::from(key), String::from(value));
+    }
+
+    /// Adds every setting in `other`, replacing those with the same key
+    pub fn overlay(&mut self, other: Settings) {
+        self.values.extend(other.values);
+    }
+
+    /// Returns the value stored under `key`, if any
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    /// Returns the settings whose keys start with `prefix`, with `prefix`
+    /// taken off their keys, in order of their keys
+    pub fn with_prefix<'a>(
+        &'a self,
+        prefix: &'a str,
+    ) -> impl Iterator<Item = (&'a str, &'a str)> + 'a {
+        self.values
+            .iter()
+            .filter_map(move |(key, value)| Some((key.strip_prefix(prefix)?, value.as_str())))
+    }
+
+    /// Returns the value stored under `key` interpreted as a boolean.
+    /// Accepts `yes`/`no`, `on`/`off`, `true`/`false` and `1`/`0`.
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        match self.get(key)?.to_lowercase().as_str() {
+            "yes" | "on" | "true" | "1" => Some(true),
+            "no" | "off" | "false" | "0" => Some(false),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Settings;
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn loads_key_value_pairs() {
+        let mut path = env::temp_dir();
+        path.push(format!("fund-settings-{}", std::process::id()));
+        fs::write(
+            &path,
+            "# reports\n\nreport.low.columns = name, amount\n  color=no  \nalias.gas = spend car = 30\n",
+        )
+        .unwrap();
+        let settings = Settings::load(&path).unwrap();
+        assert_eq!(settings.get("report.low.columns"), Some("name, amount"));
+        assert_eq!(settings.get_bool("color"), Some(false));
+        assert_eq!(settings.get("alias.gas"), Some("spend car = 30"));
+        assert_eq!(settings.get("# reports"), None);
+
+        fs::write(&path, "color = no\nno value here\n").unwrap();
+        let error = Settings::load(&path).unwrap_err().to_string();
+        assert!(
+            error.ends_with("line 2: expected 'key = value'"),
+            "{}",
+            error
+        );
+        fs::remove_file(&path).unwrap();
+
+        assert!(Settings::load(&path).unwrap().get("color").is_none());
+    }
+
+    #[test]
+    fn overlays_and_filters_settings() {
+        let mut settings = Settings::new();
+        settings.set("schedule.rent.fund", "rent");
+        settings.set("schedule.rent.spend", "900.00");
+        settings.set("color", "yes");
+        let mut profile = Settings::new();
+        profile.set("color", "off");
+        settings.overlay(profile);
+
+        assert_eq!(settings.get_bool("color"), Some(false));
+        let schedule: Vec<(&str, &str)> = settings.with_prefix("schedule.").collect();
+        assert_eq!(
+            schedule,
+            vec![("rent.fund", "rent"), ("rent.spend", "900.00")]
+        );
+        settings.set("color", "maybe");
+        assert_eq!(settings.get_bool("color"), None);
+    }
+}
@@ -0,0 +1,257 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::io;
+
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+
+use libfund::history::History;
+use libfund::{FundManager, FundManagerError};
+
+use cli;
+use hooks;
+use {execute_line, print_changes, Config, Data};
+
+/// The commands offered when completing the first word of a line
+const COMMANDS: &[&str] = &[
+    "abort", "alerts", "commit", "deposit", "diff", "exit", "export", "forecast", "graph", "help",
+    "import", "info", "new", "profile", "quit", "rate", "rename", "report", "set", "spend",
+    "stats", "transfer",
+];
+
+/// Runs an interactive shell that reads commands line by line and applies
+/// them to funds held in memory. Changes are saved by `commit` and when
+/// the shell exits, and thrown away by `abort`.
+///
+/// # Errors
+///
+/// * When the funds could not be loaded or saved
+/// * When reading from the terminal fails
+pub fn run(config: &Config) -> Result<(), FundManagerError> {
+    let mut data = Data::load(config)?;
+    let loaded = data.clone();
+    let mut saved = data.clone();
+    let mut pending = 0;
+
+    let mut editor: Editor<ShellHelper, DefaultHistory> = Editor::new().map_err(readline_error)?;
+    editor.set_helper(Some(ShellHelper::new(&data)));
+    let historyfile = config.fundfile.with_extension("shell-history");
+    // There is no history to load the first time the shell is used
+    let _ = editor.load_history(&historyfile);
+
+    println!(
+        "Type 'help' for a list of commands, 'commit' to save and 'abort' to quit without saving"
+    );
+    if config.dry_run {
+        println!("This is a dry run, changes will not be saved");
+    }
+    loop {
+        let prompt = match pending {
+            0 => String::from("fund> "),
+            1 => String::from("fund (1 change)> "),
+            _ => format!("fund ({} changes)> ", pending),
+        };
+        let line = match editor.readline(&prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(From::from(readline_error(e))),
+        };
+        let words = match cli::split_words(&line) {
+            Ok(ref words) if words.is_empty() => continue,
+            Ok(words) => words,
+            Err(e) => {
+                eprintln!("{}", e);
+                continue;
+            }
+        };
+        let _ = editor.add_history_entry(line.as_str());
+
+        match words[0].as_str() {
+            "exit" | "quit" => break,
+            "commit" => {
+                save(config, &data)?;
+                saved = data.clone();
+                pending = 0;
+            }
+            "abort" => {
+                if pending > 0 {
+                    println!("Discarded {}", plural(pending, "change"));
+                }
+                let _ = editor.save_history(&historyfile);
+                return Ok(());
+            }
+            "diff" => print_changes(&saved, &data, &config.style),
+            "help" if words.len() == 1 => print_help(),
+            _ => match execute_line(config, &words, &data) {
+                Ok(next) => {
+                    if next != data {
+                        pending += 1;
+                    }
+                    data = next;
+                }
+                Err(e) => eprintln!("Error: {}", e),
+            },
+        }
+        if let Some(helper) = editor.helper_mut() {
+            *helper = ShellHelper::new(&data);
+        }
+    }
+
+    if pending > 0 {
+        save(config, &data)?;
+    }
+    let _ = editor.save_history(&historyfile);
+    print!("{}", hooks::exit(config, "shell", &loaded, &data)?);
+    Ok(())
+}
+
+fn print_help() {
+    println!(
+        "Any fund command can be typed without the leading 'fund', e.g. 'spend grocery 12.50'."
+    );
+    println!("Use 'help <command>' or '<command> --help' for the options of a command.");
+    println!();
+    println!("  diff     Shows what changed since the last commit");
+    println!("  commit   Saves the changes");
+    println!("  abort    Quits without saving the changes");
+    println!("  exit     Saves the changes and quits, as does Ctrl-D");
+}
+
+/// Saves `data`, unless this is a dry run
+fn save(config: &Config, data: &Data) -> Result<(), io::Error> {
+    if config.dry_run {
+        println!("Dry run, nothing was saved");
+        Ok(())
+    } else {
+        data.save(config)?;
+        println!("Saved");
+        Ok(())
+    }
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {}", noun)
+    } else {
+        format!("{} {}s", count, noun)
+    }
+}
+
+fn readline_error(e: ReadlineError) -> io::Error {
+    match e {
+        ReadlineError::Io(e) => e,
+        e => io::Error::other(e.to_string()),
+    }
+}
+
+fn fund_names(funds: &FundManager) -> Vec<String> {
+    funds.into_iter().map(|(name, _)| name.clone()).collect()
+}
+
+/// Returns where the word being typed at the end of `line` starts, which
+/// is at its opening quote if it has one
+fn word_start(line: &str) -> usize {
+    let mut start = 0;
+    let mut quote = None;
+    for (index, c) in line.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c.is_whitespace() => start = index + c.len_utf8(),
+            None => {}
+        }
+    }
+    start
+}
+
+/// Returns every payee in the history, most used first
+fn payees(history: &History) -> Vec<String> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for payee in history
+        .iter()
+        .filter_map(|transaction| transaction.payee.as_ref())
+    {
+        *counts.entry(payee.as_str()).or_insert(0) += 1;
+    }
+    let mut payees: Vec<(&str, usize)> = counts.into_iter().collect();
+    payees.sort_by_key(|&(_, count)| Reverse(count));
+    payees
+        .into_iter()
+        .map(|(payee, _)| String::from(payee))
+        .collect()
+}
+
+/// Completes command names at the start of a line, the subcommands of
+/// commands that have them, past payees after `--payee`, and fund names
+/// everywhere else
+struct ShellHelper {
+    funds: Vec<String>,
+    payees: Vec<String>,
+}
+
+impl ShellHelper {
+    fn new(data: &Data) -> ShellHelper {
+        ShellHelper {
+            funds: fund_names(&data.funds),
+            payees: payees(&data.history),
+        }
+    }
+}
+
+impl Completer for ShellHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let line = &line[..pos];
+        let start = word_start(line);
+        let partial = line[start..].trim_start_matches(['"', '\'']);
+        let previous = cli::split_words(&line[..start]).unwrap_or_default();
+        let previous: Vec<&str> = previous.iter().map(String::as_str).collect();
+
+        let candidates: Vec<&str> = match previous.as_slice() {
+            [.., "--payee"] => self.payees.iter().map(String::as_str).collect(),
+            [] => COMMANDS.to_vec(),
+            ["import"] => vec!["csv", "ofx", "qif", "json"],
+            ["export"] => vec!["ledger", "hledger", "beancount", "json"],
+            ["rate"] => vec!["set", "list"],
+            ["profile"] => vec!["list", "create", "use"],
+            ["help"] => COMMANDS.to_vec(),
+            _ => self.funds.iter().map(String::as_str).collect(),
+        };
+        let pairs = candidates
+            .into_iter()
+            .filter(|candidate| candidate.starts_with(partial))
+            .map(|candidate| Pair {
+                display: String::from(candidate),
+                replacement: if candidate.contains(char::is_whitespace) {
+                    format!("\"{}\" ", candidate)
+                } else {
+                    format!("{} ", candidate)
+                },
+            })
+            .collect();
+        Ok((start, pairs))
+    }
+}
+
+impl Hinter for ShellHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ShellHelper {}
+
+impl Validator for ShellHelper {}
+
+impl Helper for ShellHelper {}
@@ -0,0 +1,176 @@
+use std::env;
+use std::error::Error;
+use std::io;
+use std::io::IsTerminal;
+
+use terminal_size::{terminal_size, Width};
+
+use libfund::currency::Currency;
+use libfund::money::{MoneyFormat, NegativeStyle};
+
+use settings::Settings;
+
+/// How output should look: whether it is colored and how amounts of
+/// money are written
+#[derive(Debug, Clone)]
+pub struct Style {
+    pub color: bool,
+    pub money: MoneyFormat,
+    /// The currency new funds use and totals are reported in
+    pub currency: Currency,
+    /// A symbol to use instead of the default currency's own symbol
+    pub symbol: Option<String>,
+}
+
+impl Style {
+    /// Builds the output style from the environment and the config file.
+    /// Money is formatted for the locale in `LC_ALL`, `LC_MONETARY` or
+    /// `LANG`, then adjusted by the `money.symbol_after`, `money.decimal`,
+    /// `money.thousands` and `money.negative` settings. The default
+    /// currency comes from the `currency` setting, and `money.symbol`
+    /// replaces its symbol.
+    ///
+    /// # Errors
+    ///
+    /// * When a money setting has an invalid value
+    /// * When the currency is not a known ISO 4217 code
+    pub fn new(settings: &Settings) -> Result<Style, Box<dyn Error + Send + Sync>> {
+        let locale = ["LC_ALL", "LC_MONETARY", "LANG"]
+            .iter()
+            .filter_map(|var| env::var(var).ok())
+            .find(|value| !value.is_empty());
+        let mut money = locale
+            .and_then(|locale| MoneyFormat::for_locale(&locale))
+            .unwrap_or_default();
+
+        if let Some(symbol_after) = settings.get_bool("money.symbol_after") {
+            money.with_symbol_after(symbol_after);
+        }
+        if let Some(decimal) = settings.get("money.decimal") {
+            money.with_decimal_separator(single_char("money.decimal", decimal)?);
+        }
+        if let Some(thousands) = settings.get("money.thousands") {
+            if thousands.is_empty() || thousands == "none" {
+                money.with_thousands_separator(None);
+            } else {
+                money.with_thousands_separator(Some(single_char("money.thousands", thousands)?));
+            }
+        }
+        match settings.get("money.negative") {
+            Some("minus") => {
+                money.with_negative_style(NegativeStyle::Minus);
+            }
+            Some("parentheses") => {
+                money.with_negative_style(NegativeStyle::Parentheses);
+            }
+            Some(other) => {
+                return Err(From::from(format!(
+                    "money.negative must be 'minus' or 'parentheses', not '{}'",
+                    other
+                )))
+            }
+            None => {}
+        }
+
+        let currency = match settings.get("currency") {
+            Some(code) => code.parse()?,
+            None => Currency::default(),
+        };
+
+        Ok(Style {
+            color: color_enabled(settings),
+            money,
+            currency,
+            symbol: settings.get("money.symbol").map(String::from),
+        })
+    }
+
+    /// Returns the format for amounts in `currency`
+    pub fn money_for(&self, currency: Currency) -> MoneyFormat {
+        let mut money = currency.money_format(&self.money);
+        if currency == self.currency {
+            if let Some(ref symbol) = self.symbol {
+                money.with_symbol(symbol);
+            }
+        }
+        money
+    }
+}
+
+fn single_char(key: &str, value: &str) -> Result<char, String> {
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(format!("{} must be a single character", key)),
+    }
+}
+
+/// The terminal colors used when rendering funds
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Color {
+    Red,
+    Yellow,
+    Green,
+    Bold,
+}
+
+impl Color {
+    fn code(self) -> &'static str {
+        match self {
+            Color::Red => "31",
+            Color::Yellow => "33",
+            Color::Green => "32",
+            Color::Bold => "1",
+        }
+    }
+}
+
+/// Returns whether output should be colored. Color is turned off when
+/// stdout is not a terminal, when the `NO_COLOR` environment variable is
+/// set, or when `color = no` is in the config file.
+pub fn color_enabled(settings: &Settings) -> bool {
+    let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    settings.get_bool("color").unwrap_or(true) && !no_color && io::stdout().is_terminal()
+}
+
+/// Wraps `text` in the escape codes for `color` if `enabled` is true
+pub fn paint(text: &str, color: Color, enabled: bool) -> String {
+    if enabled {
+        format!("\x1b[{}m{}\x1b[0m", color.code(), text)
+    } else {
+        String::from(text)
+    }
+}
+
+/// Returns a bar `width` characters wide, filled in proportion to
+/// `fraction`. Fractions outside of `0.0..=1.0` are clamped.
+pub fn progress_bar(fraction: f64, width: usize) -> String {
+    let fraction = if fraction.is_nan() {
+        0.0
+    } else {
+        fraction.clamp(0.0, 1.0)
+    };
+    let filled = (fraction * width as f64).floor() as usize;
+    let mut bar = String::with_capacity(width * 3);
+    for i in 0..width {
+        bar.push(if i < filled { '█' } else { '░' });
+    }
+    bar
+}
+
+/// Returns the number of characters `text` takes up on screen
+pub fn width(text: &str) -> usize {
+    text.chars().count()
+}
+
+/// Returns how many characters fit on a line of the terminal, falling
+/// back to the `COLUMNS` environment variable and then to 80
+pub fn terminal_width() -> usize {
+    if let Some((Width(width), _)) = terminal_size() {
+        return usize::from(width);
+    }
+    env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+        .unwrap_or(80)
+}
@@ -0,0 +1,534 @@
+use std::io;
+
+use chrono::Local;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+
+use libfund::alerts;
+use libfund::history::Transaction;
+use libfund::money;
+use libfund::{Fund, FundManagerError};
+
+use hooks;
+use report;
+use term;
+use {alert_thresholds, describe_alert, Config, Data};
+
+/// How many transactions the detail pane lists at most
+const HISTORY_LINES: usize = 50;
+
+/// Runs a full screen interface for browsing funds and changing them.
+/// Changes are saved when quitting with `q`, unless this is a dry run, and
+/// thrown away with `Q`.
+///
+/// # Errors
+///
+/// * When the funds could not be loaded or saved
+/// * When the terminal could not be set up or read from
+pub fn run(config: &Config) -> Result<(), FundManagerError> {
+    let loaded = Data::load(config)?;
+    let mut app = App::new(config, loaded.clone());
+    let mut terminal = ratatui::try_init()?;
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    if result? && !config.dry_run {
+        app.data.save(config)?;
+        print!("{}", hooks::exit(config, "tui", &loaded, &app.data)?);
+    }
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Action {
+    Deposit,
+    Spend,
+    Transfer,
+    Rename,
+}
+
+impl Action {
+    /// The command this action carries out, as hooks are told
+    fn command(self) -> &'static str {
+        match self {
+            Action::Deposit => "deposit",
+            Action::Spend => "spend",
+            Action::Transfer => "transfer",
+            Action::Rename => "rename",
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            Action::Deposit => "Deposit",
+            Action::Spend => "Spend",
+            Action::Transfer => "Transfer",
+            Action::Rename => "Rename",
+        }
+    }
+
+    fn labels(self) -> &'static [&'static str] {
+        match self {
+            Action::Deposit | Action::Spend => &["Amount"],
+            Action::Transfer => &["To fund", "Amount"],
+            Action::Rename => &["New name"],
+        }
+    }
+}
+
+/// A form asking for what an action needs, one field at a time
+struct Dialog {
+    action: Action,
+    fund: String,
+    values: Vec<String>,
+    focus: usize,
+}
+
+impl Dialog {
+    fn new(action: Action, fund: &str) -> Dialog {
+        Dialog {
+            action,
+            fund: String::from(fund),
+            values: vec![String::new(); action.labels().len()],
+            focus: 0,
+        }
+    }
+}
+
+struct App<'a> {
+    config: &'a Config,
+    data: Data,
+    names: Vec<String>,
+    list: ListState,
+    dialog: Option<Dialog>,
+    /// The outcome of the last action, shown at the bottom
+    message: Option<Result<String, String>>,
+    changed: bool,
+}
+
+impl<'a> App<'a> {
+    fn new(config: &'a Config, data: Data) -> App<'a> {
+        let mut app = App {
+            config,
+            data,
+            names: Vec::new(),
+            list: ListState::default(),
+            dialog: None,
+            message: None,
+            changed: false,
+        };
+        app.refresh_names();
+        app
+    }
+
+    /// Draws and handles keys until the user quits, returning whether the
+    /// changes should be saved
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<bool, io::Error> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                if self.dialog.is_some() {
+                    self.dialog_key(key);
+                    continue;
+                }
+                match key.code {
+                    KeyCode::Char('q') => return Ok(true),
+                    KeyCode::Char('Q') => return Ok(false),
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        return Ok(false)
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => self.list.select_previous(),
+                    KeyCode::Down | KeyCode::Char('j') => self.list.select_next(),
+                    KeyCode::Home | KeyCode::Char('g') => self.list.select_first(),
+                    KeyCode::End | KeyCode::Char('G') => self.list.select_last(),
+                    KeyCode::Char('d') => self.open(Action::Deposit),
+                    KeyCode::Char('s') => self.open(Action::Spend),
+                    KeyCode::Char('t') => self.open(Action::Transfer),
+                    KeyCode::Char('r') => self.open(Action::Rename),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn selected(&self) -> Option<&str> {
+        let index = self.list.selected()?;
+        self.names
+            .get(index.min(self.names.len().saturating_sub(1)))
+            .map(String::as_str)
+    }
+
+    fn refresh_names(&mut self) {
+        self.names = (&self.data.funds)
+            .into_iter()
+            .map(|(name, _)| name.clone())
+            .collect();
+        if self.names.is_empty() {
+            self.list.select(None);
+        } else if self.list.selected().is_none() {
+            self.list.select(Some(0));
+        }
+    }
+
+    fn open(&mut self, action: Action) {
+        let fund = match self.selected() {
+            Some(fund) => String::from(fund),
+            None => return,
+        };
+        self.message = None;
+        self.dialog = Some(Dialog::new(action, &fund));
+    }
+
+    fn dialog_key(&mut self, key: KeyEvent) {
+        let submit = {
+            let dialog = match self.dialog {
+                Some(ref mut dialog) => dialog,
+                None => return,
+            };
+            match key.code {
+                KeyCode::Esc => {
+                    self.dialog = None;
+                    return;
+                }
+                KeyCode::Char(c) => {
+                    dialog.values[dialog.focus].push(c);
+                    false
+                }
+                KeyCode::Backspace => {
+                    dialog.values[dialog.focus].pop();
+                    false
+                }
+                KeyCode::Tab | KeyCode::Down => {
+                    dialog.focus = (dialog.focus + 1) % dialog.values.len();
+                    false
+                }
+                KeyCode::BackTab | KeyCode::Up => {
+                    dialog.focus = (dialog.focus + dialog.values.len() - 1) % dialog.values.len();
+                    false
+                }
+                KeyCode::Enter if dialog.focus + 1 < dialog.values.len() => {
+                    dialog.focus += 1;
+                    false
+                }
+                KeyCode::Enter => true,
+                _ => false,
+            }
+        };
+        if submit {
+            if let Some(dialog) = self.dialog.take() {
+                let before = self.data.clone();
+                let result = self.apply(&dialog).and_then(|message| {
+                    hooks::check(self.config, dialog.action.command(), &before, &self.data)
+                        .map(|_| message)
+                        .map_err(|e| e.to_string())
+                });
+                if result.is_err() {
+                    self.data = before;
+                }
+                match result {
+                    Ok(message) => {
+                        self.changed = true;
+                        self.message = Some(Ok(message));
+                        self.refresh_names();
+                    }
+                    Err(message) => {
+                        self.message = Some(Err(message));
+                        self.dialog = Some(dialog);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Carries out what a dialog asks for, returning a message describing
+    /// what was done or what went wrong
+    fn apply(&mut self, dialog: &Dialog) -> Result<String, String> {
+        let today = Local::now().date_naive();
+        let name = dialog.fund.as_str();
+        let currency = self
+            .data
+            .funds
+            .fund(name)
+            .map_err(|e| e.to_string())?
+            .currency;
+        let amount = |value: &str| match money::parse_amount(value, currency) {
+            Ok(amount) if amount > 0 => Ok(amount),
+            Ok(_) => Err(format!("'{}' is not a positive amount", value.trim())),
+            Err(e) => Err(e.to_string()),
+        };
+        let funds = &mut self.data.funds;
+        let history = &mut self.data.history;
+        match dialog.action {
+            Action::Deposit | Action::Spend => {
+                let amount = amount(&dialog.values[0])?;
+                let thresholds =
+                    alert_thresholds(&self.config.settings, funds).map_err(|e| e.to_string())?;
+                let fund = funds.fund_mut(name).map_err(|e| e.to_string())?;
+                let before = *fund;
+                let (leg, verb) = if dialog.action == Action::Deposit {
+                    fund.deposit(amount);
+                    (amount, "Deposited")
+                } else {
+                    fund.spend(amount);
+                    (-amount, "Spent")
+                };
+                history.push(Transaction::new(today).with_leg(name, leg).build());
+                let money = self.config.style.money_for(fund.currency);
+                let preposition = if leg > 0 { "into" } else { "from" };
+                let mut message = format!(
+                    "{} {} {} {}",
+                    verb,
+                    money.format(i64::from(amount)),
+                    preposition,
+                    name
+                );
+                if let Some(alert) = alerts::crossed(&before, fund, thresholds.get(name).cloned()) {
+                    message.push_str(&format!(
+                        ". Warning: {}",
+                        describe_alert(name, fund, alert, &self.config.style)
+                    ));
+                }
+                Ok(message)
+            }
+            Action::Transfer => {
+                let to = dialog.values[0].trim();
+                let amount = amount(&dialog.values[1])?;
+                let transaction = funds
+                    .transfer(name, to, amount, &self.data.rates, today)
+                    .map_err(|e| e.to_string())?;
+                history.push(transaction);
+                Ok(format!("Transferred from {} to {}", name, to))
+            }
+            Action::Rename => {
+                let new_name = dialog.values[0].trim();
+                if new_name.is_empty() {
+                    return Err(String::from("please supply a new name"));
+                }
+                funds.rename(name, new_name).map_err(|e| e.to_string())?;
+                history.rename_fund(name, new_name);
+                if let Some(index) = (&*funds).into_iter().position(|(fund, _)| fund == new_name) {
+                    self.list.select(Some(index));
+                }
+                Ok(format!("Renamed {} to {}", name, new_name))
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, totals, footer] = Layout::vertical([
+            Constraint::Min(3),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [list_area, detail_area] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .areas(main);
+
+        self.draw_list(frame, list_area);
+        self.draw_detail(frame, detail_area);
+        frame.render_widget(Paragraph::new(self.totals_line()), totals);
+
+        let footer_line = match self.message {
+            Some(Ok(ref message)) => Line::from(message.as_str()),
+            Some(Err(ref message)) => Line::from(Span::styled(message.as_str(), self.color(Color::Red))),
+            None => Line::from(
+                "↑↓ move  d deposit  s spend  t transfer  r rename  q save and quit  Q quit without saving",
+            ),
+        };
+        frame.render_widget(Paragraph::new(footer_line), footer);
+
+        if let Some(ref dialog) = self.dialog {
+            self.draw_dialog(frame, dialog);
+        }
+    }
+
+    fn draw_list(&mut self, frame: &mut Frame, area: Rect) {
+        let name_width = self
+            .names
+            .iter()
+            .map(|name| term::width(name))
+            .max()
+            .unwrap_or(0);
+        let items: Vec<ListItem> = (&self.data.funds)
+            .into_iter()
+            .map(|(name, fund)| {
+                let money = self.config.style.money_for(fund.currency);
+                let progress = fund.progress();
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!("{:<width$}  ", name, width = name_width)),
+                    Span::raw(format!("{:>10}  ", money.format(i64::from(fund.amount)))),
+                    Span::styled(term::progress_bar(progress, 12), self.status_style(fund)),
+                    Span::raw(format!(" {:>4.0}%", progress * 100.0)),
+                ]))
+            })
+            .collect();
+        let title = if self.changed {
+            "Funds (unsaved)"
+        } else {
+            "Funds"
+        };
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, area, &mut self.list);
+    }
+
+    fn draw_detail(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default().borders(Borders::ALL);
+        let name = match self.selected() {
+            Some(name) => name,
+            None => {
+                let text = Paragraph::new("No funds yet. Create one with 'fund new <name>'");
+                frame.render_widget(text.block(block), area);
+                return;
+            }
+        };
+        let fund = match self.data.funds.fund(name) {
+            Ok(fund) => fund,
+            Err(_) => return,
+        };
+        let money = self.config.style.money_for(fund.currency);
+        let format = |amount: i32| money.format(i64::from(amount));
+
+        let mut lines = vec![
+            Line::from(format!("Amount:     {}", format(fund.amount))),
+            Line::from(format!("Goal:       {}", format(fund.goal))),
+            Line::from(format!("Remaining:  {}", money.format(fund.remaining()))),
+            Line::from(vec![
+                Span::raw("Progress:   "),
+                Span::styled(
+                    term::progress_bar(fund.progress(), 20),
+                    self.status_style(fund),
+                ),
+                Span::raw(format!(" {:.0}%", fund.progress() * 100.0)),
+            ]),
+            Line::from(format!("Currency:   {}", fund.currency)),
+            Line::from(""),
+            Line::from(Span::styled(
+                "History",
+                self.color(Color::Reset).add_modifier(Modifier::BOLD),
+            )),
+        ];
+        let transactions: Vec<&Transaction> = self
+            .data
+            .history
+            .iter()
+            .rev()
+            .filter(|transaction| transaction.legs.iter().any(|leg| leg.fund == name))
+            .take(HISTORY_LINES)
+            .collect();
+        if transactions.is_empty() {
+            lines.push(Line::from("No transactions"));
+        }
+        for transaction in transactions {
+            for leg in transaction.legs.iter().filter(|leg| leg.fund == name) {
+                let other = transaction.legs.iter().find(|other| other.fund != name);
+                let description = match (other, leg.amount < 0) {
+                    (Some(other), true) => format!("to {}", other.fund),
+                    (Some(other), false) => format!("from {}", other.fund),
+                    (None, true) => String::from("spent"),
+                    (None, false) => String::from("deposited"),
+                };
+                let description = match (&transaction.payee, &transaction.memo) {
+                    (Some(payee), Some(memo)) => format!("{}  {}: {}", description, payee, memo),
+                    (Some(note), None) | (None, Some(note)) => format!("{}  {}", description, note),
+                    (None, None) => description,
+                };
+                let color = if leg.amount < 0 {
+                    Color::Red
+                } else {
+                    Color::Green
+                };
+                lines.push(Line::from(vec![
+                    Span::raw(format!("{}  ", transaction.date)),
+                    Span::styled(format!("{:>10}", format(leg.amount)), self.color(color)),
+                    Span::raw(format!("  {}", description)),
+                ]));
+            }
+        }
+        frame.render_widget(Paragraph::new(lines).block(block.title(name)), area);
+    }
+
+    fn totals_line(&self) -> Line<'static> {
+        let currency = self.config.style.currency;
+        match self.data.funds.total(currency, &self.data.rates) {
+            Ok(total) => {
+                let money = self.config.style.money_for(currency);
+                Line::from(format!(
+                    " Total {} of {}, {} to go",
+                    money.format(i64::from(total.amount)),
+                    money.format(i64::from(total.goal)),
+                    money.format(total.remaining())
+                ))
+            }
+            Err(e) => Line::from(format!(" Total unavailable: {}", e)),
+        }
+    }
+
+    fn draw_dialog(&self, frame: &mut Frame, dialog: &Dialog) {
+        let labels = dialog.action.labels();
+        let area = centered(frame.area(), 50, labels.len() as u16 + 2);
+        let label_width = labels.iter().map(|label| label.len()).max().unwrap_or(0);
+        let lines: Vec<Line> = labels
+            .iter()
+            .zip(&dialog.values)
+            .map(|(label, value)| {
+                let label = format!("{}:", label);
+                Line::from(format!(
+                    "{:<width$} {}",
+                    label,
+                    value,
+                    width = label_width + 1
+                ))
+            })
+            .collect();
+        let title = format!("{} {}", dialog.action.title(), dialog.fund);
+        let block = Block::default().borders(Borders::ALL).title(title);
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+
+        let value = &dialog.values[dialog.focus];
+        let x = area.x + 1 + label_width as u16 + 2 + term::width(value) as u16;
+        let y = area.y + 1 + dialog.focus as u16;
+        frame.set_cursor_position((x.min(area.right().saturating_sub(2)), y));
+    }
+
+    fn status_style(&self, fund: &Fund) -> Style {
+        self.color(match report::status_color(fund) {
+            term::Color::Red => Color::Red,
+            term::Color::Yellow => Color::Yellow,
+            term::Color::Green => Color::Green,
+            term::Color::Bold => Color::Reset,
+        })
+    }
+
+    /// Returns a style with `color` as its foreground, unless color is
+    /// turned off
+    fn color(&self, color: Color) -> Style {
+        if self.config.style.color {
+            Style::default().fg(color)
+        } else {
+            Style::default()
+        }
+    }
+}
+
+/// Returns a rectangle `width` columns wide and `height` rows high in the
+/// middle of `area`
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}